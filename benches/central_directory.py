@@ -0,0 +1,67 @@
+"""Compare metadata calls that read the central directory with calls served from its cache.
+
+The first metadata call on an archive reads the central directory,
+and later calls reuse that snapshot instead of traversing the archive again.
+Run from the repository root, after building the extension:
+
+    python -m benches.central_directory
+"""
+
+from __future__ import annotations
+
+import io
+import timeit
+import zipfile
+from typing import Callable
+
+import nd_zipfile
+
+MEMBERS = 20_000
+REPEAT = 20
+
+
+def make_archive() -> bytes:
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        for index in range(MEMBERS):
+            archive.writestr(f"dir{index % 100}/member{index}.txt", b"")
+
+    return buffer.getvalue()
+
+
+def report(label: str, method: Callable[[nd_zipfile.ZipFile], object], data: bytes) -> None:
+    def cold() -> None:
+        with nd_zipfile.ZipFile(io.BytesIO(data)) as archive:
+            method(archive)
+
+    def open_only() -> None:
+        with nd_zipfile.ZipFile(io.BytesIO(data)):
+            pass
+
+    warm_archive = nd_zipfile.ZipFile(io.BytesIO(data))
+    method(warm_archive)
+
+    # Opening is timed separately, so that only the cost of the call itself is compared.
+    open_time = min(timeit.repeat(open_only, number=1, repeat=REPEAT))
+    cold_time = min(timeit.repeat(cold, number=1, repeat=REPEAT)) - open_time
+    warm_time = min(timeit.repeat(lambda: method(warm_archive), number=1, repeat=REPEAT))
+    warm_archive.close()
+
+    print(
+        f"{label:>12}: first call {cold_time * 1e3:8.3f} ms, "
+        f"cached call {warm_time * 1e3:8.3f} ms, "
+        f"{cold_time / warm_time:6.1f}x"
+    )
+
+
+def main() -> None:
+    data = make_archive()
+    print(f"{MEMBERS} members")
+    report("namelist", lambda archive: archive.namelist(), data)
+    report("infolist", lambda archive: archive.infolist(), data)
+    report("getinfo", lambda archive: archive.getinfo("dir0/member0.txt"), data)
+    report("len", len, data)
+
+
+if __name__ == "__main__":
+    main()
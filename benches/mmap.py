@@ -0,0 +1,72 @@
+"""Compare random-access member reads of a path archive with and without `mmap`.
+
+With `mmap=True`, the archive is memory-mapped,
+so reading a member doesn't need a seek and read syscall per chunk.
+Run from the repository root, after building the extension:
+
+    python -m benches.mmap
+"""
+
+from __future__ import annotations
+
+import os
+import random
+import statistics
+import tempfile
+import time
+import zipfile
+
+import nd_zipfile
+
+MEMBERS = 2_000
+MEMBER_SIZE = 16 * 1024
+READS = 20_000
+
+
+def make_archive(path: str) -> list[str]:
+    names = [f"dir{index % 50}/member{index}.bin" for index in range(MEMBERS)]
+    with zipfile.ZipFile(path, "w") as archive:
+        for name in names:
+            archive.writestr(name, os.urandom(MEMBER_SIZE))
+
+    return names
+
+
+def measure(path: str, names: list[str], mmap: bool) -> list[float]:
+    # Every run reads the same members in the same order.
+    order = random.Random(0).choices(names, k=READS)
+    latencies = []
+    with nd_zipfile.ZipFile(path, "r", mmap=mmap) as archive:
+        # Read the central directory up front, so it isn't counted in the first read.
+        archive.prefetch()
+        for name in order:
+            start = time.perf_counter()
+            archive.read(name)
+            latencies.append(time.perf_counter() - start)
+
+    return latencies
+
+
+def report(label: str, latencies: list[float]) -> None:
+    quantiles = statistics.quantiles(latencies, n=100)
+    print(
+        f"{label:>10}: mean {statistics.fmean(latencies) * 1e6:8.2f} us, "
+        f"median {quantiles[49] * 1e6:8.2f} us, "
+        f"p99 {quantiles[98] * 1e6:8.2f} us"
+    )
+
+
+def main() -> None:
+    with tempfile.TemporaryDirectory() as directory:
+        path = os.path.join(directory, "archive.zip")
+        names = make_archive(path)
+        print(f"{READS} random reads of {MEMBERS} members of {MEMBER_SIZE} bytes")
+
+        # Warm the page cache, so both runs read from memory.
+        measure(path, names, mmap=False)
+        report("file reads", measure(path, names, mmap=False))
+        report("mmap", measure(path, names, mmap=True))
+
+
+if __name__ == "__main__":
+    main()
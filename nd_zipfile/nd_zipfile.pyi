@@ -1,47 +1,295 @@
-from typing import Self
-from types import TracebackType
-
-ZIP_STORED: int
-ZIP_DEFLATED: int
-ZIP_BZIP2: int
-ZIP_LZMA: int
-
-class ZipInfo:
-    filename: str
-    compress_type: int
-    compress_level: int | None
-    def __init__(self, filename: str = "NoName") -> None: ...
-
-class ZipExtFile:
-    def read(self) -> bytes: ...
-    def write(self, buffer: bytes) -> None: ...
-    def close(self) -> None: ...
-    def __enter__(self) -> Self: ...
-    def __exit__(
-        self,
-        exc_type: type[BaseException] | None,
-        exc_value: BaseException | None,
-        traceback: TracebackType | None,
-    ) -> None: ...
-
-class ZipFile:
-    def __init__(
-        self,
-        file: str,
-        mode: str = "r",
-        compression: int = ZIP_STORED,
-        allowZip64: bool = True,
-        compresslevel: int | None = None,
-    ) -> None: ...
-    def close(self) -> None: ...
-    def open(
-        self, name: str | ZipInfo, mode: str = "r", pwd: bytes | None = None
-    ) -> ZipExtFile: ...
-    def namelist(self) -> list[str]: ...
-    def __enter__(self) -> Self: ...
-    def __exit__(
-        self,
-        exc_type: type[BaseException] | None,
-        exc_value: BaseException | None,
-        traceback: TracebackType | None,
-    ) -> None: ...
+from datetime import datetime
+from os import PathLike
+from pathlib import Path
+from typing import IO, Any, Callable, Iterable, Literal, Self
+from types import TracebackType
+
+ZIP_STORED: int
+ZIP_DEFLATED: int
+ZIP_DEFLATED64: int
+ZIP_BZIP2: int
+ZIP_LZMA: int
+
+class BadZipFile(Exception): ...
+class ZipBombError(BadZipFile): ...
+class LargeZipFile(Exception): ...
+
+class ZipInfo:
+    filename: str
+    compress_type: int
+    compress_level: int | None
+    date_time: tuple[int, int, int, int, int, int]
+    @property
+    def compress_size(self) -> int: ...
+    @property
+    def file_size(self) -> int: ...
+    @property
+    def CRC(self) -> int: ...
+    @property
+    def header_offset(self) -> int: ...
+    def __init__(self, filename: str = "NoName") -> None: ...
+    @staticmethod
+    def from_bytes(arcname: str, data: bytes) -> ZipInfo: ...
+    def mtime_datetime(self) -> datetime: ...
+
+class ZipExtFile:
+    @property
+    def name(self) -> str: ...
+    def read(self, size: int | None = -1) -> bytes: ...
+    def readline(self, size: int | None = -1) -> bytes: ...
+    def readlines(self, hint: int | None = -1) -> list[bytes]: ...
+    def peek(self, n: int = 1) -> bytes: ...
+    def readinto(self, buffer: bytearray | memoryview) -> int: ...
+    @property
+    def closed(self) -> bool: ...
+    def flush(self) -> None: ...
+    def readable(self) -> bool: ...
+    def writable(self) -> bool: ...
+    def seekable(self) -> bool: ...
+    def tell(self) -> int: ...
+    def seek(self, offset: int, whence: int = 0) -> int: ...
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> bytes: ...
+    def write(self, buffer: bytes) -> None: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> None: ...
+
+class ZipSeekableFile:
+    def read(self, size: int | None = -1) -> bytes: ...
+    def seek(self, offset: int, whence: int = 0) -> int: ...
+    def tell(self) -> int: ...
+    def readable(self) -> bool: ...
+    def seekable(self) -> bool: ...
+    def writable(self) -> bool: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> None: ...
+
+class ZipChunkIterator:
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> bytes: ...
+
+class ZipRawIterator:
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> tuple[ZipInfo, bytes]: ...
+
+class ZipNameIterator:
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> str: ...
+
+class ZipEntryIterator:
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> tuple[ZipInfo, ZipExtFile]: ...
+
+class ZipFile:
+    def __init__(
+        self,
+        file: str | IO[bytes],
+        mode: str = "r",
+        compression: int = ZIP_STORED,
+        allowZip64: bool = True,
+        compresslevel: int | None = None,
+        *,
+        compatibility: Literal["modern", "legacy"] = "modern",
+        deflate_backend: Literal["zlib", "zopfli"] = "zlib",
+        read_timeout: float | None = None,
+        require_plaintext: bool = False,
+        max_entry_size: int | None = None,
+        mmap: bool = False,
+        prepend: bytes | None = None,
+    ) -> None: ...
+    @staticmethod
+    def from_fd(
+        fd: int,
+        mode: str = "r",
+        compression: int = ZIP_STORED,
+        allowZip64: bool = True,
+        compresslevel: int | None = None,
+        *,
+        compatibility: Literal["modern", "legacy"] = "modern",
+        deflate_backend: Literal["zlib", "zopfli"] = "zlib",
+        require_plaintext: bool = False,
+        max_entry_size: int | None = None,
+    ) -> ZipFile: ...
+    def close(self) -> None: ...
+    def reopen(self) -> None: ...
+    def open(
+        self,
+        name: str | ZipInfo,
+        mode: str = "r",
+        pwd: bytes | None = None,
+        *,
+        ignore_case: bool = False,
+        force_method: int | None = None,
+        lazy: bool = False,
+        force_zip64: bool = False,
+        max_ratio: float | None = None,
+        expected_size: int | None = None,
+    ) -> ZipExtFile: ...
+    def open_file(self, name: str | ZipInfo, pwd: bytes | None = None) -> ZipExtFile: ...
+    def open_seekable(self, name: str | ZipInfo, pwd: bytes | None = None) -> ZipSeekableFile: ...
+    def read(
+        self, name: str | ZipInfo, pwd: bytes | None = None, *, salvage: bool = False
+    ) -> bytes: ...
+    def iter_raw(self) -> ZipRawIterator: ...
+    def iter_chunks(
+        self,
+        name: str | ZipInfo,
+        chunk_size: int = 65536,
+        pwd: bytes | None = None,
+        *,
+        callback: Callable[[int], object] | None = None,
+        max_ratio: float | None = None,
+    ) -> ZipChunkIterator: ...
+    def write_all(
+        self,
+        mapping: dict[str, bytes | str],
+        *,
+        auto_store: bool = False,
+    ) -> None: ...
+    def write(
+        self,
+        filename: str | PathLike[str],
+        arcname: str | None = None,
+        compress_type: int | None = None,
+        compresslevel: int | None = None,
+    ) -> None: ...
+    def mkdir(self, zinfo_or_directory_name: str | ZipInfo, mode: int = 0o777) -> None: ...
+    def writestr(
+        self,
+        zinfo_or_arcname: str | ZipInfo,
+        data: bytes | str,
+        compress_type: int | None = None,
+        compresslevel: int | None = None,
+        *,
+        override_crc: int | None = None,
+    ) -> None: ...
+    def write_iter(
+        self,
+        name: str | ZipInfo,
+        chunks: Iterable[bytes],
+        *,
+        force_zip64: bool = False,
+        auto_store: bool = False,
+    ) -> None: ...
+    def read_matching(self, predicate: Callable[[ZipInfo], bool]) -> dict[str, bytes]: ...
+    def digests(
+        self,
+        name: str | ZipInfo,
+        algorithms: list[str],
+        pwd: bytes | None = None,
+    ) -> dict[str, str]: ...
+    def read_range(
+        self,
+        name: str | ZipInfo,
+        start: int,
+        length: int,
+        pwd: bytes | None = None,
+    ) -> bytes: ...
+    def read_json(self, name: str | ZipInfo, pwd: bytes | None = None) -> Any: ...
+    def read_text(
+        self,
+        name: str | ZipInfo,
+        pwd: bytes | None = None,
+        *,
+        encoding: str = "utf-8",
+        newline: str | None = None,
+    ) -> str: ...
+    def read_verified(
+        self, name: str | ZipInfo, expected_sha256: str, pwd: bytes | None = None
+    ) -> bytes: ...
+    def read_into_array(
+        self, name: str | ZipInfo, dtype_itemsize: int, pwd: bytes | None = None
+    ) -> bytes: ...
+    def extract(
+        self,
+        member: str | ZipInfo,
+        path: str | PathLike[str] | None = None,
+        pwd: bytes | None = None,
+        *,
+        preserve_ownership: bool = False,
+    ) -> str: ...
+    def extractall(
+        self,
+        path: str | PathLike[str] | None = None,
+        members: list[str | ZipInfo] | None = None,
+        pwd: bytes | None = None,
+        *,
+        skip_unchanged: bool = False,
+        max_total_size: int | None = None,
+        max_files: int | None = None,
+        preserve_ownership: bool = False,
+    ) -> None: ...
+    def read_into_buf(
+        self, name: str | ZipInfo, buf: bytearray, pwd: bytes | None = None
+    ) -> int: ...
+    def open_with_info(
+        self,
+        name: str | ZipInfo,
+        pwd: bytes | None = None,
+        *,
+        ignore_case: bool = False,
+    ) -> tuple[ZipInfo, ZipExtFile]: ...
+    def namelist(self) -> list[str]: ...
+    def getinfo(self, name: str) -> ZipInfo: ...
+    def infolist(self) -> list[ZipInfo]: ...
+    def setpassword(self, pwd: bytes | None) -> None: ...
+    def testzip(self) -> str | None: ...
+    def audit(self) -> list[tuple[str, int, int, bool]]: ...
+    def printdir(self, file: IO[str] | None = None) -> None: ...
+    def glob(self, pattern: str) -> list[str]: ...
+    def diff_dir(self, directory: str | PathLike[str]) -> dict[str, list[str]]: ...
+    def modified_since(self, timestamp: float | datetime) -> list[str]: ...
+    def toplevel(self) -> set[str]: ...
+    def largest(self, n: int = 10) -> list[tuple[str, int]]: ...
+    def size_by_extension(self) -> dict[str, int]: ...
+    def unsupported_entries(self) -> list[str]: ...
+    def metadata_json(self) -> str: ...
+    def prefetch(self) -> None: ...
+    def is_zip64(self) -> bool: ...
+    def dominant_compression(self) -> int | None: ...
+    def method_histogram(self) -> dict[int, int]: ...
+    def disk_info(self) -> dict[str, int]: ...
+    def detect_encoding(self) -> tuple[str, float]: ...
+    def explode(self, output_path: str | PathLike[str]) -> None: ...
+    def sorted_copy(self, output_path: str | PathLike[str]) -> None: ...
+    def to_tar(
+        self,
+        output_path: str | PathLike[str],
+        compression: Literal["gz", "bz2"] | None = None,
+    ) -> None: ...
+    def split(self, max_volume_size: int) -> list[Path]: ...
+    def __len__(self) -> int: ...
+    def __iter__(self) -> ZipNameIterator: ...
+    def stream_entries(self, pwd: bytes | None = None) -> ZipEntryIterator: ...
+    def __contains__(self, name: str) -> bool: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> None: ...
+
+def archives_equal(
+    path_a: str | PathLike[str],
+    path_b: str | PathLike[str],
+    compare: Literal["content", "metadata"] = "content",
+) -> bool: ...
+def estimate_compressed_size(
+    data: bytes | Iterable[bytes],
+    compress_type: int,
+    compresslevel: int | None = None,
+) -> int: ...
+def is_zipfile(filename: str | PathLike[str] | IO[bytes]) -> bool: ...
@@ -0,0 +1,83 @@
+use crate::compress_type;
+use crate::date_time_tuple;
+use crate::file::zip_error_to_py;
+use crate::file::ArchiveFile;
+use crate::DateTimeTuple;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use std::collections::HashMap;
+use zip::CompressionMethod;
+use zip::HasZipMetadata;
+use zip::ZipArchive;
+
+/// A snapshot of the central directory of an archive.
+///
+/// This is built once and reused by metadata methods,
+/// so that they don't need to lock and traverse the archive on every call.
+#[derive(Debug)]
+pub(crate) struct CentralDirectory {
+    entries: Vec<CentralDirectoryEntry>,
+    /// The index of each entry by name.
+    indices: HashMap<String, usize>,
+}
+
+impl CentralDirectory {
+    /// Read the central directory of an archive.
+    pub(crate) fn new(archive: &mut ZipArchive<ArchiveFile>) -> PyResult<Self> {
+        let mut entries = Vec::with_capacity(archive.len());
+        let mut indices = HashMap::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let file = archive
+                .by_index_raw(index)
+                .map_err(|error| zip_error_to_py(error, PyRuntimeError::new_err))?;
+
+            indices.insert(file.name().into(), index);
+            entries.push(CentralDirectoryEntry {
+                name: file.name().into(),
+                name_raw: file.name_raw().into(),
+                compress_type: compress_type(file.compression()),
+                compress_size: file.compressed_size(),
+                file_size: file.size(),
+                crc: file.crc32(),
+                date_time: date_time_tuple(file.last_modified()),
+                header_offset: file.header_start(),
+                zip64: file.get_metadata().large_file,
+                encrypted: file.encrypted(),
+                #[allow(deprecated)]
+                supported: !matches!(file.compression(), CompressionMethod::Unsupported(_)),
+            });
+        }
+
+        Ok(Self { entries, indices })
+    }
+
+    /// Get the index of the entry with the given name, like `ZipArchive::index_for_name`.
+    pub(crate) fn index_for_name(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Get the entries, in central directory order.
+    pub(crate) fn entries(&self) -> &[CentralDirectoryEntry] {
+        &self.entries
+    }
+}
+
+/// The metadata of a single entry in the central directory.
+#[derive(Debug)]
+pub(crate) struct CentralDirectoryEntry {
+    pub(crate) name: String,
+    /// The name as stored, before decoding, for guessing the encoding of legacy archives.
+    pub(crate) name_raw: Vec<u8>,
+    pub(crate) compress_type: u16,
+    pub(crate) compress_size: u64,
+    pub(crate) file_size: u64,
+    pub(crate) crc: u32,
+    pub(crate) date_time: DateTimeTuple,
+    /// The offset of the local header of the entry.
+    pub(crate) header_offset: u64,
+    /// Whether the entry has a Zip64 extra field.
+    pub(crate) zip64: bool,
+    pub(crate) encrypted: bool,
+    /// Whether the compression method of the entry can be decompressed.
+    pub(crate) supported: bool,
+}
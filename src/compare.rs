@@ -0,0 +1,240 @@
+use crate::central_directory::CentralDirectoryEntry;
+use crate::read::sanitize_member_path;
+use crate::BadZipFile;
+use flate2::Crc;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use zip::ZipArchive;
+
+/// The size of the buffers used when streaming member contents.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// What to compare when checking if two archives are equal.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum Comparison {
+    /// Compare the decompressed contents of members.
+    Content,
+    /// Compare the central directory metadata of members.
+    Metadata,
+}
+
+impl TryFrom<&str> for Comparison {
+    type Error = PyErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "content" => Ok(Self::Content),
+            "metadata" => Ok(Self::Metadata),
+            _ => Err(PyValueError::new_err(format!(
+                "{value} is not a known comparison, expected \"content\" or \"metadata\""
+            ))),
+        }
+    }
+}
+
+/// Check if two archives have the same set of member names,
+/// and members with the same contents or metadata.
+pub(crate) fn archives_equal(
+    path_a: &Path,
+    path_b: &Path,
+    comparison: Comparison,
+) -> PyResult<bool> {
+    let mut archive_a = open_archive(path_a)?;
+    let mut archive_b = open_archive(path_b)?;
+
+    let names_a: BTreeSet<String> = archive_a.file_names().map(String::from).collect();
+    let names_b: BTreeSet<&str> = archive_b.file_names().collect();
+    if !names_a.iter().map(String::as_str).eq(names_b) {
+        return Ok(false);
+    }
+
+    for name in names_a.iter() {
+        let equal = match comparison {
+            Comparison::Content => contents_equal(&mut archive_a, &mut archive_b, name)?,
+            Comparison::Metadata => metadata_equal(&mut archive_a, &mut archive_b, name)?,
+        };
+
+        if !equal {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// The differences between the members of an archive and the files in a directory.
+#[derive(Debug, Default)]
+pub(crate) struct DirDiff {
+    /// Members that don't exist in the directory.
+    pub(crate) missing: Vec<String>,
+    /// Files in the directory that aren't members, relative to it, with `/` separators.
+    pub(crate) extra: Vec<String>,
+    /// Files whose size or CRC doesn't match their member.
+    pub(crate) changed: Vec<String>,
+}
+
+/// Compare the members of an archive against the files in a directory, as if it were extracted there.
+///
+/// Member names are sanitized like when extracting.
+/// Directory members only need to exist, and directories on disk are never extra.
+pub(crate) fn diff_dir(entries: &[CentralDirectoryEntry], directory: &Path) -> PyResult<DirDiff> {
+    let mut diff = DirDiff::default();
+
+    let mut member_paths = BTreeSet::new();
+    for entry in entries {
+        let relative_path = sanitize_member_path(&entry.name);
+        let path = directory.join(&relative_path);
+
+        if entry.name.ends_with('/') {
+            if !path.is_dir() {
+                diff.missing.push(entry.name.clone());
+            }
+            continue;
+        }
+        member_paths.insert(relative_path);
+
+        match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => {
+                if metadata.len() != entry.file_size || file_crc(&path)? != entry.crc {
+                    diff.changed.push(entry.name.clone());
+                }
+            }
+            Ok(_metadata) => diff.missing.push(entry.name.clone()),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                diff.missing.push(entry.name.clone());
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let mut files = Vec::new();
+    list_files(directory, PathBuf::new(), &mut files)?;
+    diff.extra = files
+        .into_iter()
+        .filter(|file| !member_paths.contains(file))
+        .map(|file| {
+            file.iter()
+                .map(|component| component.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect();
+    diff.extra.sort();
+
+    Ok(diff)
+}
+
+/// Recursively list the files in a directory, relative to the top directory.
+///
+/// Symlinks to directories are not followed.
+fn list_files(directory: &Path, relative_path: PathBuf, files: &mut Vec<PathBuf>) -> PyResult<()> {
+    for entry in std::fs::read_dir(directory.join(&relative_path))? {
+        let entry = entry?;
+        let path = relative_path.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() {
+            list_files(directory, path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the CRC-32 of a file, one chunk at a time.
+fn file_crc(path: &Path) -> PyResult<u32> {
+    let mut file = File::open(path)?;
+    let mut crc = Crc::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        match file.read(&mut buffer)? {
+            0 => return Ok(crc.sum()),
+            n => crc.update(&buffer[..n]),
+        }
+    }
+}
+
+fn open_archive(path: &Path) -> PyResult<ZipArchive<File>> {
+    let file = File::open(path)?;
+    ZipArchive::new(file).map_err(|error| BadZipFile::new_err(error.to_string()))
+}
+
+/// Compare the decompressed contents of a member in both archives, one chunk at a time.
+fn contents_equal(
+    archive_a: &mut ZipArchive<File>,
+    archive_b: &mut ZipArchive<File>,
+    name: &str,
+) -> PyResult<bool> {
+    let mut file_a = archive_a
+        .by_name(name)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+    let mut file_b = archive_b
+        .by_name(name)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+
+    // Cheap rejection, before decompressing anything.
+    if file_a.size() != file_b.size() || file_a.crc32() != file_b.crc32() {
+        return Ok(false);
+    }
+
+    let mut buffer_a = vec![0; CHUNK_SIZE];
+    let mut buffer_b = vec![0; CHUNK_SIZE];
+    loop {
+        let len_a = fill(&mut file_a, &mut buffer_a)?;
+        let len_b = fill(&mut file_b, &mut buffer_b)?;
+
+        if buffer_a[..len_a] != buffer_b[..len_b] {
+            return Ok(false);
+        }
+
+        if len_a == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Compare the central directory metadata of a member in both archives.
+fn metadata_equal(
+    archive_a: &mut ZipArchive<File>,
+    archive_b: &mut ZipArchive<File>,
+    name: &str,
+) -> PyResult<bool> {
+    let missing = || PyRuntimeError::new_err(format!("File {name} does not exist"));
+    let index_a = archive_a.index_for_name(name).ok_or_else(missing)?;
+    let index_b = archive_b.index_for_name(name).ok_or_else(missing)?;
+
+    let file_a = archive_a
+        .by_index_raw(index_a)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+    let file_b = archive_b
+        .by_index_raw(index_b)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+
+    Ok(file_a.compression() == file_b.compression()
+        && file_a.last_modified() == file_b.last_modified()
+        && file_a.crc32() == file_b.crc32()
+        && file_a.compressed_size() == file_b.compressed_size()
+        && file_a.size() == file_b.size()
+        && file_a.unix_mode() == file_b.unix_mode()
+        && file_a.comment() == file_b.comment())
+}
+
+/// Read until the buffer is full or the reader is exhausted, returning the number of bytes read.
+fn fill(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..])? {
+            0 => break,
+            n => len += n,
+        }
+    }
+
+    Ok(len)
+}
@@ -0,0 +1,130 @@
+use pyo3::exceptions::PyUnicodeDecodeError;
+use pyo3::exceptions::PyUnicodeEncodeError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// The encodings that are guessed, as Python codec names,
+/// each with an optional codec for its commonly used subset.
+///
+/// Multi-byte encodings decode many byte sequences into rare characters from their extensions,
+/// so only characters in the common subset are counted as plausible.
+/// Multi-byte encodings come first, as single-byte ones decode anything,
+/// and earlier encodings win ties.
+const CANDIDATE_ENCODINGS: &[(&str, Option<&str>)] = &[
+    ("cp932", Some("shift_jis")),
+    ("gbk", Some("gb2312")),
+    ("big5", None),
+    ("cp949", Some("euc_kr")),
+    ("cp1252", None),
+    ("cp1251", None),
+    ("cp866", None),
+    ("cp437", None),
+];
+
+/// Guess the encoding of raw member names, returning a Python codec name and a confidence.
+///
+/// Names that are plain ASCII or valid UTF-8 are reported as such, with full confidence.
+/// Otherwise, the names are decoded with every candidate encoding,
+/// and each is scored by the share of non-ASCII characters that decode to plausible letters.
+/// The confidence is that share for the best candidate,
+/// divided between candidates that score just as well.
+pub(crate) fn detect_encoding<'a>(
+    py: Python<'_>,
+    names: impl IntoIterator<Item = &'a [u8]>,
+) -> PyResult<(String, f64)> {
+    let names: Vec<&[u8]> = names.into_iter().filter(|name| !name.is_ascii()).collect();
+    if names.is_empty() {
+        return Ok(("ascii".into(), 1.0));
+    }
+    if names.iter().all(|name| std::str::from_utf8(name).is_ok()) {
+        return Ok(("utf-8".into(), 1.0));
+    }
+
+    let mut scores = Vec::with_capacity(CANDIDATE_ENCODINGS.len());
+    for (encoding, subset) in CANDIDATE_ENCODINGS {
+        if let Some(score) = score_encoding(py, &names, encoding, *subset)? {
+            scores.push((*encoding, score));
+        }
+    }
+
+    // cp437 is the fallback of the zip format, and decodes anything.
+    let (encoding, score) = scores
+        .iter()
+        .copied()
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .unwrap_or(("cp437", 0.0));
+    let ties = scores
+        .iter()
+        .filter(|(_encoding, candidate_score)| *candidate_score == score)
+        .count()
+        .max(1);
+
+    Ok((encoding.into(), score / ties as f64))
+}
+
+/// Score how plausible an encoding is for the names, or return `None` if they don't decode.
+fn score_encoding(
+    py: Python<'_>,
+    names: &[&[u8]],
+    encoding: &str,
+    subset: Option<&str>,
+) -> PyResult<Option<f64>> {
+    let mut plausible = 0;
+    let mut total = 0;
+    for name in names {
+        let decoded = PyBytes::new(py, name).call_method1(intern!(py, "decode"), (encoding,));
+        let decoded: String = match decoded {
+            Ok(decoded) => decoded.extract()?,
+            Err(error) if error.is_instance_of::<PyUnicodeDecodeError>(py) => {
+                return Ok(None);
+            }
+            Err(error) => return Err(error),
+        };
+
+        for c in decoded.chars().filter(|c| !c.is_ascii()) {
+            total += 1;
+            if is_plausible(py, c, subset)? {
+                plausible += 1;
+            }
+        }
+    }
+
+    if total == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(f64::from(plausible) / f64::from(total)))
+}
+
+/// Check if a character is likely to appear in a file name,
+/// and is in the common subset of its encoding, if there is one.
+///
+/// Halfwidth katakana are letters, but are rare in names,
+/// and are what Chinese and Korean text often decodes to with cp932.
+fn is_plausible(py: Python<'_>, c: char, subset: Option<&str>) -> PyResult<bool> {
+    if !c.is_alphabetic() || ('\u{FF61}'..='\u{FF9F}').contains(&c) {
+        return Ok(false);
+    }
+
+    let Some(subset) = subset else {
+        return Ok(true);
+    };
+
+    let mut buffer = [0; 4];
+    let encoded = c
+        .encode_utf8(&mut buffer)
+        .into_pyobject(py)?
+        .call_method1(intern!(py, "encode"), (subset,));
+    match encoded {
+        Ok(_encoded) => Ok(true),
+        Err(error) if error.is_instance_of::<PyUnicodeEncodeError>(py) => Ok(false),
+        Err(error) => Err(error),
+    }
+}
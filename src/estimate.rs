@@ -0,0 +1,124 @@
+use crate::CompressionKind;
+use bzip2::write::BzEncoder;
+use flate2::write::DeflateEncoder;
+use pyo3::exceptions::PyNotImplementedError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::borrow::Cow;
+use std::io::Write;
+
+/// Compress data without storing it, to find out how large it would be in an archive.
+pub(crate) enum Estimator {
+    Stored(CountingWriter),
+    Deflated(DeflateEncoder<CountingWriter>),
+    Bzip2(BzEncoder<CountingWriter>),
+}
+
+impl Estimator {
+    /// Make an estimator for a compression method and level.
+    ///
+    /// Levels are validated like they are when writing.
+    pub(crate) fn new(
+        compression_kind: CompressionKind,
+        compression_level: Option<u8>,
+    ) -> PyResult<Self> {
+        let writer = CountingWriter { count: 0 };
+
+        match compression_kind {
+            CompressionKind::Stored => Ok(Self::Stored(writer)),
+            CompressionKind::Deflated => {
+                let compression = match compression_level {
+                    Some(compression_level) if !(0..=9).contains(&compression_level) => {
+                        return Err(PyValueError::new_err(format!(
+                            "invalid ZIP_DEFLATED compresslevel {compression_level}"
+                        )));
+                    }
+                    Some(compression_level) => flate2::Compression::new(compression_level.into()),
+                    None => flate2::Compression::default(),
+                };
+
+                Ok(Self::Deflated(DeflateEncoder::new(writer, compression)))
+            }
+            CompressionKind::Bzip2 => {
+                let compression = match compression_level {
+                    Some(compression_level) if !(1..=9).contains(&compression_level) => {
+                        return Err(PyValueError::new_err(format!(
+                            "invalid ZIP_BZIP2 compresslevel {compression_level}"
+                        )));
+                    }
+                    Some(compression_level) => bzip2::Compression::new(compression_level.into()),
+                    None => bzip2::Compression::default(),
+                };
+
+                Ok(Self::Bzip2(BzEncoder::new(writer, compression)))
+            }
+            CompressionKind::Deflate64 => Err(PyNotImplementedError::new_err(
+                "compressing with ZIP_DEFLATED64 is currently unsupported",
+            )),
+            CompressionKind::Lzma => Err(PyNotImplementedError::new_err(
+                "compressing with ZIP_LZMA is currently unsupported",
+            )),
+        }
+    }
+
+    /// Feed data from a bytes-like object, or an iterable of bytes-like chunks.
+    pub(crate) fn feed(&mut self, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        if let Ok(data) = data.extract::<Cow<'_, [u8]>>() {
+            self.write_all(&data)?;
+            return Ok(());
+        }
+
+        for chunk in data.try_iter()? {
+            let chunk = chunk?;
+            let chunk: Cow<'_, [u8]> = chunk.extract()?;
+            self.write_all(&chunk)?;
+        }
+
+        Ok(())
+    }
+
+    /// Flush the compressor, returning the compressed size.
+    pub(crate) fn finish(self) -> PyResult<u64> {
+        let writer = match self {
+            Self::Stored(writer) => writer,
+            Self::Deflated(encoder) => encoder.finish()?,
+            Self::Bzip2(encoder) => encoder.finish()?,
+        };
+
+        Ok(writer.count)
+    }
+}
+
+impl Write for Estimator {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Stored(writer) => writer.write(buf),
+            Self::Deflated(encoder) => encoder.write(buf),
+            Self::Bzip2(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Stored(writer) => writer.flush(),
+            Self::Deflated(encoder) => encoder.flush(),
+            Self::Bzip2(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// A writer that discards data, counting how much was written.
+pub(crate) struct CountingWriter {
+    count: u64,
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
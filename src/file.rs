@@ -0,0 +1,285 @@
+use crate::mmap::Mmap;
+use parking_lot::Mutex;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyTimeoutError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyo3::types::PyCFunction;
+use pyo3::types::PyDict;
+use std::fs::File;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use zip::result::ZipError;
+
+/// The file backing an archive.
+#[derive(Debug)]
+pub(crate) enum ArchiveFile {
+    /// A file on the filesystem.
+    Os(File),
+    /// A Python file-like object, like `io.BytesIO`.
+    Python(PyFileLike),
+    /// A memory-mapped file on the filesystem, which is read-only.
+    Mapped(Cursor<Mmap>),
+}
+
+impl Read for ArchiveFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Os(file) => file.read(buf),
+            Self::Python(file) => file.read(buf),
+            Self::Mapped(file) => file.read(buf),
+        }
+    }
+}
+
+impl Write for ArchiveFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Os(file) => file.write(buf),
+            Self::Python(file) => file.write(buf),
+            Self::Mapped(_file) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "memory-mapped archives are read-only",
+            )),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Os(file) => file.flush(),
+            Self::Python(file) => file.flush(),
+            Self::Mapped(_file) => Ok(()),
+        }
+    }
+}
+
+impl Seek for ArchiveFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            Self::Os(file) => file.seek(pos),
+            Self::Python(file) => file.seek(pos),
+            Self::Mapped(file) => file.seek(pos),
+        }
+    }
+}
+
+/// An adapter for using a Python file-like object as a Rust reader, writer, and seeker.
+///
+/// Each operation acquires the GIL and calls the matching Python method.
+#[derive(Debug)]
+pub(crate) struct PyFileLike {
+    object: PyObject,
+    /// How long a single `read` call may take, if limited.
+    read_timeout: Option<Duration>,
+    /// The thread running timed reads, started by the first one.
+    reader: Option<ReaderThread>,
+    /// Whether a read timed out, leaving a read running in the background.
+    timed_out: bool,
+}
+
+/// A daemon thread that calls `read` on a file-like object for each requested size.
+///
+/// The thread exits once this is dropped, after finishing any read it is running.
+#[derive(Debug)]
+struct ReaderThread {
+    requests: mpsc::Sender<usize>,
+    // Archives must be `Sync`, so the receiver is behind an uncontended mutex.
+    results: Mutex<mpsc::Receiver<PyResult<PyObject>>>,
+}
+
+impl ReaderThread {
+    /// Start a reader thread for an object.
+    fn start(py: Python<'_>, object: PyObject) -> PyResult<Self> {
+        let (requests, request_receiver) = mpsc::channel::<usize>();
+        let (result_sender, results) = mpsc::channel();
+        // The receiver is only used by the thread, but the closure must be `Sync` too.
+        let request_receiver = Mutex::new(request_receiver);
+        let target = PyCFunction::new_closure(py, None, None, move |args, _kwargs| {
+            let py = args.py();
+            while let Ok(size) = py.allow_threads(|| request_receiver.lock().recv()) {
+                let result = object
+                    .bind(py)
+                    .call_method1(intern!(py, "read"), (size,))
+                    .map(Bound::unbind);
+
+                if result_sender.send(result).is_err() {
+                    break;
+                }
+            }
+        })?;
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "target"), target)?;
+        kwargs.set_item(intern!(py, "daemon"), true)?;
+        py.import(intern!(py, "threading"))?
+            .call_method(intern!(py, "Thread"), (), Some(&kwargs))?
+            .call_method0(intern!(py, "start"))?;
+
+        Ok(Self {
+            requests,
+            results: Mutex::new(results),
+        })
+    }
+}
+
+impl PyFileLike {
+    /// Wrap a Python file-like object.
+    ///
+    /// The object must have a `seek` method,
+    /// as well as `read` and `write` methods for the operations it will be used for.
+    pub(crate) fn new(object: PyObject) -> Self {
+        Self {
+            object,
+            read_timeout: None,
+            reader: None,
+            timed_out: false,
+        }
+    }
+
+    /// Limit how long a single `read` call may take before raising a `TimeoutError`.
+    ///
+    /// Python code cannot be interrupted, so timed reads are run on a separate daemon thread,
+    /// which is started by the first read and reused by the rest.
+    /// After a timeout, the read is left running and the object cannot be used again,
+    /// as its position is unknown.
+    pub(crate) fn with_read_timeout(mut self, read_timeout: Option<Duration>) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    /// Check if an object looks like a file-like object that can be wrapped.
+    pub(crate) fn is_file_like(object: &Bound<'_, PyAny>) -> PyResult<bool> {
+        let py = object.py();
+
+        Ok(object.hasattr(intern!(py, "seek"))?
+            && (object.hasattr(intern!(py, "read"))? || object.hasattr(intern!(py, "write"))?))
+    }
+}
+
+impl PyFileLike {
+    /// Call `read` on the reader thread, waiting for at most `read_timeout`.
+    fn read_with_timeout<'py>(
+        &mut self,
+        py: Python<'py>,
+        size: usize,
+        read_timeout: Duration,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => self
+                .reader
+                .insert(ReaderThread::start(py, self.object.clone_ref(py))?),
+        };
+
+        reader.requests.send(size).map_err(|_error| {
+            PyRuntimeError::new_err("read from file object exited without a result")
+        })?;
+
+        let results = &reader.results;
+        match py.allow_threads(|| results.lock().recv_timeout(read_timeout)) {
+            Ok(result) => Ok(result?.into_bound(py)),
+            Err(RecvTimeoutError::Timeout) => {
+                self.timed_out = true;
+                Err(PyTimeoutError::new_err(format!(
+                    "read from file object did not complete within {} seconds",
+                    read_timeout.as_secs_f64()
+                )))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(PyRuntimeError::new_err(
+                "read from file object exited without a result",
+            )),
+        }
+    }
+}
+
+impl Read for PyFileLike {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.timed_out {
+            return Err(
+                PyTimeoutError::new_err("a previous read from the file object timed out").into(),
+            );
+        }
+
+        Python::with_gil(|py| {
+            let data = match self.read_timeout {
+                Some(read_timeout) => self.read_with_timeout(py, buf.len(), read_timeout)?,
+                None => self
+                    .object
+                    .bind(py)
+                    .call_method1(intern!(py, "read"), (buf.len(),))?,
+            };
+            let data = data.downcast::<PyBytes>().map_err(PyErr::from)?.as_bytes();
+
+            // Guard against misbehaving objects that return more than requested.
+            let len = data.len().min(buf.len());
+            buf[..len].copy_from_slice(&data[..len]);
+
+            Ok(len)
+        })
+    }
+}
+
+impl Write for PyFileLike {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Python::with_gil(|py| {
+            let written = self
+                .object
+                .bind(py)
+                .call_method1(intern!(py, "write"), (PyBytes::new(py, buf),))?;
+
+            // Some file-like objects return None instead of the number of bytes written.
+            if written.is_none() {
+                Ok(buf.len())
+            } else {
+                Ok(written.extract()?)
+            }
+        })
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Python::with_gil(|py| {
+            let object = self.object.bind(py);
+            if object.hasattr(intern!(py, "flush"))? {
+                object.call_method0(intern!(py, "flush"))?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl Seek for PyFileLike {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        Python::with_gil(|py| {
+            let object = self.object.bind(py);
+            let seek = intern!(py, "seek");
+            let position = match pos {
+                SeekFrom::Start(offset) => object.call_method1(seek, (offset, 0))?,
+                SeekFrom::Current(offset) => object.call_method1(seek, (offset, 1))?,
+                SeekFrom::End(offset) => object.call_method1(seek, (offset, 2))?,
+            };
+
+            Ok(position.extract()?)
+        })
+    }
+}
+
+/// Convert a zip crate error to a Python exception.
+///
+/// Exceptions raised by Python file objects, like read timeouts, are passed through unchanged.
+/// Other errors are converted with `new_err`.
+pub(crate) fn zip_error_to_py(error: ZipError, new_err: impl FnOnce(String) -> PyErr) -> PyErr {
+    match error {
+        ZipError::Io(error) if error.get_ref().is_some_and(|inner| inner.is::<PyErr>()) => {
+            error.into()
+        }
+        error => new_err(error.to_string()),
+    }
+}
@@ -0,0 +1,78 @@
+/// Check if a member name matches a shell-style glob pattern.
+///
+/// Patterns are matched per path component, split on `/`.
+/// `*` matches any run of characters within a component,
+/// `?` matches a single character within a component,
+/// `[...]` matches a character class (negated with `[!...]`),
+/// and a component consisting of only `**` matches zero or more whole components.
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let name: Vec<&str> = name.split('/').collect();
+
+    match_components(&pattern, &name)
+}
+
+fn match_components(pattern: &[&str], name: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((&"**", rest)) => (0..=name.len()).any(|skip| match_components(rest, &name[skip..])),
+        Some((component, rest)) => match name.split_first() {
+            Some((name_component, name_rest)) => {
+                let component: Vec<char> = component.chars().collect();
+                let name_component: Vec<char> = name_component.chars().collect();
+
+                match_component(&component, &name_component) && match_components(rest, name_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| match_component(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && match_component(rest, &name[1..]),
+        Some(('[', rest)) => match (match_class(rest, name.first().copied()), name.split_first()) {
+            (Some((true, rest)), Some((_, name_rest))) => match_component(rest, name_rest),
+            (Some(_), _) => false,
+            // An unterminated class is treated as a literal '['.
+            (None, _) => name.first() == Some(&'[') && match_component(rest, &name[1..]),
+        },
+        Some((c, rest)) => name.first() == Some(c) && match_component(rest, &name[1..]),
+    }
+}
+
+/// Match a character class, with the pattern starting after the opening `[`.
+///
+/// Returns whether the character matched and the remaining pattern after the closing `]`,
+/// or `None` if the class is unterminated.
+fn match_class(pattern: &[char], c: Option<char>) -> Option<(bool, &[char])> {
+    let (negated, mut pattern) = match pattern.split_first() {
+        Some(('!', rest)) => (true, rest),
+        _ => (false, pattern),
+    };
+
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        match pattern {
+            [] => return None,
+            [']', rest @ ..] if !first => {
+                let matched = c.is_some() && matched != negated;
+                return Some((matched, rest));
+            }
+            [start, '-', end, rest @ ..] if *end != ']' => {
+                if let Some(c) = c {
+                    matched |= (*start..=*end).contains(&c);
+                }
+                pattern = rest;
+            }
+            [start, rest @ ..] => {
+                matched |= c == Some(*start);
+                pattern = rest;
+            }
+        }
+        first = false;
+    }
+}
@@ -1,42 +1,104 @@
+mod central_directory;
+mod compare;
+mod encoding;
+mod estimate;
+mod file;
+mod glob;
+mod listing;
+mod local_time;
+mod mmap;
+mod ownership;
 mod read;
+mod tar;
 mod write;
 
+use self::central_directory::CentralDirectoryEntry;
+use self::compare::Comparison;
+use self::estimate::Estimator;
+use self::file::ArchiveFile;
+use self::file::PyFileLike;
+use self::mmap::Mmap;
+use self::read::ExtractOptions;
+use self::read::MemberOptions;
+use self::read::RawEntries;
 use self::read::ReadZipExtFile;
 use self::read::ReadZipFile;
+use self::tar::TarCompression;
 use self::write::WriteZipFile;
 use crate::write::WriteZipExtFile;
+use flate2::Crc;
 use pyo3::create_exception;
 use pyo3::exceptions::PyException;
+use pyo3::exceptions::PyKeyError;
 use pyo3::exceptions::PyNotImplementedError;
 use pyo3::exceptions::PyValueError;
+use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
 use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
 use pyo3::types::PyString;
 use pyo3::types::PyStringMethods;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
 use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use zip::CompressionMethod;
+use zip::DateTime;
+use zip::ZipArchive;
 
-const ZIP_STORED: u8 = 0;
-const ZIP_DEFLATED: u8 = 8;
-const ZIP_BZIP2: u8 = 12;
-const ZIP_LZMA: u8 = 14;
+const ZIP_STORED: u16 = 0;
+const ZIP_DEFLATED: u16 = 8;
+const ZIP_DEFLATED64: u16 = 9;
+const ZIP_BZIP2: u16 = 12;
+const ZIP_LZMA: u16 = 14;
+
+/// The earliest timestamp representable in a zip file, used by default.
+const DEFAULT_DATE_TIME: DateTimeTuple = (1980, 1, 1, 0, 0, 0);
+
+/// A timestamp as (year, month, day, hour, minute, second).
+type DateTimeTuple = (u16, u8, u8, u8, u8, u8);
 
 create_exception!(nd_zip, BadZipFile, PyException, "File is not a zip file");
+create_exception!(
+    nd_zip,
+    ZipBombError,
+    BadZipFile,
+    "Member decompressed to more than the allowed ratio of its compressed size"
+);
+create_exception!(
+    nd_zip,
+    LargeZipFile,
+    PyException,
+    "Member would require ZIP64 extensions, which are disabled"
+);
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 enum CompressionKind {
     Stored,
     Deflated,
+    Deflate64,
     Bzip2,
     Lzma,
 }
 
-impl TryFrom<u8> for CompressionKind {
+impl TryFrom<u16> for CompressionKind {
     type Error = PyErr;
 
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
         match value {
             ZIP_STORED => Ok(Self::Stored),
             ZIP_DEFLATED => Ok(Self::Deflated),
+            ZIP_DEFLATED64 => Ok(Self::Deflate64),
             ZIP_BZIP2 => Ok(Self::Bzip2),
             ZIP_LZMA => Ok(Self::Lzma),
             _ => Err(PyNotImplementedError::new_err(format!(
@@ -46,84 +108,493 @@ impl TryFrom<u8> for CompressionKind {
     }
 }
 
-impl From<CompressionKind> for u8 {
+impl From<CompressionKind> for u16 {
     fn from(value: CompressionKind) -> Self {
         match value {
             CompressionKind::Stored => ZIP_STORED,
             CompressionKind::Deflated => ZIP_DEFLATED,
+            CompressionKind::Deflate64 => ZIP_DEFLATED64,
             CompressionKind::Bzip2 => ZIP_BZIP2,
             CompressionKind::Lzma => ZIP_LZMA,
         }
     }
 }
 
+/// Get the compression method id, as stored in the archive, for a compression method.
+fn compress_type(method: CompressionMethod) -> u16 {
+    #[allow(deprecated)]
+    match method {
+        CompressionMethod::Stored => 0,
+        CompressionMethod::Deflated => 8,
+        CompressionMethod::Deflate64 => 9,
+        CompressionMethod::Bzip2 => 12,
+        CompressionMethod::Lzma => 14,
+        CompressionMethod::Zstd => 93,
+        CompressionMethod::Xz => 95,
+        CompressionMethod::Aes => 99,
+        CompressionMethod::Unsupported(method) => method,
+        _ => u16::MAX,
+    }
+}
+
+/// Convert a zip timestamp into a tuple, using the default for missing timestamps.
+fn date_time_tuple(date_time: Option<DateTime>) -> DateTimeTuple {
+    match date_time {
+        Some(date_time) => (
+            date_time.year(),
+            date_time.month(),
+            date_time.day(),
+            date_time.hour(),
+            date_time.minute(),
+            date_time.second(),
+        ),
+        None => DEFAULT_DATE_TIME,
+    }
+}
+
+/// The compatibility level of written archives.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Compatibility {
+    /// Use any feature the zip crate supports.
+    Modern,
+    /// Avoid features that old unzip tools lack,
+    /// like Zip64 extensions and compression methods other than stored and deflated.
+    /// Non-ASCII member names are rejected, as they would need the UTF-8 flag.
+    Legacy,
+}
+
+impl TryFrom<&str> for Compatibility {
+    type Error = PyErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "modern" => Ok(Self::Modern),
+            "legacy" => Ok(Self::Legacy),
+            _ => Err(PyValueError::new_err(format!(
+                "{value} is not a known compatibility level, expected \"modern\" or \"legacy\""
+            ))),
+        }
+    }
+}
+
+/// The deflate implementation used when writing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum DeflateBackend {
+    /// zlib, through flate2.
+    ///
+    /// This is fast, and takes compression levels 0 to 9.
+    Zlib,
+    /// Zopfli.
+    ///
+    /// This is much slower, but compresses better.
+    /// Compression levels are taken as an iteration count, from 1 to 255.
+    Zopfli,
+}
+
+impl TryFrom<&str> for DeflateBackend {
+    type Error = PyErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "zlib" => Ok(Self::Zlib),
+            "zopfli" => Ok(Self::Zopfli),
+            _ => Err(PyValueError::new_err(format!(
+                "{value} is not a known deflate backend, expected \"zlib\" or \"zopfli\""
+            ))),
+        }
+    }
+}
+
+/// The mode an archive is opened in.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum Mode {
+    Read,
+    Write,
+    Append,
+}
+
+impl TryFrom<&str> for Mode {
+    type Error = PyErr;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "r" => Ok(Self::Read),
+            "w" => Ok(Self::Write),
+            "a" => Ok(Self::Append),
+            "x" => Err(PyNotImplementedError::new_err(
+                "ZipFile mode 'x' is currently unsupported",
+            )),
+            _ => Err(PyValueError::new_err(
+                "ZipFile requires mode 'r', 'w', 'x', or 'a'",
+            )),
+        }
+    }
+}
+
+/// Check that a decompression ratio limit is a positive number.
+fn validate_max_ratio(max_ratio: Option<f64>) -> PyResult<Option<f64>> {
+    match max_ratio {
+        Some(max_ratio) if max_ratio.is_nan() || max_ratio <= 0.0 => Err(PyValueError::new_err(
+            format!("max_ratio must be a positive number, got {max_ratio}"),
+        )),
+        max_ratio => Ok(max_ratio),
+    }
+}
+
+/// Check that the limits passed when opening an archive apply to its mode.
+fn check_limits_mode(
+    mode: Mode,
+    require_plaintext: bool,
+    max_entry_size: Option<u64>,
+) -> PyResult<()> {
+    if require_plaintext && mode != Mode::Read {
+        return Err(PyValueError::new_err(
+            "require_plaintext is only supported when reading",
+        ));
+    }
+
+    if max_entry_size.is_some() && mode == Mode::Read {
+        return Err(PyValueError::new_err(
+            "max_entry_size is only supported when writing",
+        ));
+    }
+
+    Ok(())
+}
+
+/// The error raised by every method that would modify an archive opened for reading.
+fn read_only_error() -> PyErr {
+    PyValueError::new_err("archive opened as read-only")
+}
+
+/// The error raised by every method that needs to read an archive opened for writing.
+fn write_only_error() -> PyErr {
+    PyValueError::new_err("archive opened as write-only")
+}
+
+/// The error for a member that is not in the archive, matching `zipfile`.
+fn missing_member(name: &str) -> PyErr {
+    PyKeyError::new_err(format!("There is no item named '{name}' in the archive"))
+}
+
 #[derive(Debug)]
 enum ZipFileInner {
     Read(ReadZipFile),
     Write(WriteZipFile),
 }
 
+/// How an archive was opened from a path, kept so that it can be reopened.
+#[derive(Debug, Clone)]
+struct PathOptions {
+    path: PathBuf,
+    mode: Mode,
+    compression: u16,
+    compresslevel: Option<u8>,
+    compatibility: Compatibility,
+    deflate_backend: DeflateBackend,
+    require_plaintext: bool,
+    max_entry_size: Option<u64>,
+    allow_zip64: bool,
+    mmap: bool,
+    prepend: Option<Vec<u8>>,
+}
+
 #[pyclass]
 pub struct ZipFile {
     file: ZipFileInner,
+    /// How the archive was opened, if it was opened from a path.
+    path_options: Option<PathOptions>,
+}
+
+impl ZipFile {
+    /// Open an archive from a path.
+    fn from_path(options: PathOptions) -> PyResult<Self> {
+        let mut file = match options.mode {
+            Mode::Read => {
+                let file = File::open(&options.path)?;
+                // Fall back to reading the file normally if it cannot be mapped.
+                match options.mmap.then(|| Mmap::map(&file)) {
+                    Some(Ok(mapping)) => ArchiveFile::Mapped(Cursor::new(mapping)),
+                    _ => ArchiveFile::Os(file),
+                }
+            }
+            Mode::Write => ArchiveFile::Os(File::create(&options.path)?),
+            Mode::Append => ArchiveFile::Os(
+                OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create(true)
+                    .truncate(false)
+                    .open(&options.path)?,
+            ),
+        };
+        if let (Mode::Write, Some(prepend)) = (options.mode, &options.prepend) {
+            file.write_all(prepend)?;
+        }
+
+        let mut zip_file = Self::from_archive_file(
+            file,
+            Some(options.path.clone()),
+            options.mode,
+            options.compression,
+            options.compresslevel,
+            options.compatibility,
+            options.deflate_backend,
+        )?;
+        zip_file.apply_limits(
+            options.require_plaintext,
+            options.max_entry_size,
+            options.allow_zip64,
+        )?;
+        zip_file.path_options = Some(options);
+
+        Ok(zip_file)
+    }
+
+    /// Apply the limits that are checked by the inner archive.
+    fn apply_limits(
+        &mut self,
+        require_plaintext: bool,
+        max_entry_size: Option<u64>,
+        allow_zip64: bool,
+    ) -> PyResult<()> {
+        match &mut self.file {
+            // Scan the central directory up front, so encrypted archives are rejected on open.
+            ZipFileInner::Read(file) if require_plaintext => file.require_plaintext(),
+            ZipFileInner::Read(_file) => Ok(()),
+            ZipFileInner::Write(file) => {
+                file.set_max_entry_size(max_entry_size);
+                file.set_allow_zip64(allow_zip64);
+                Ok(())
+            }
+        }
+    }
+
+    /// Open an archive backed by an already-open file.
+    fn from_archive_file(
+        file: ArchiveFile,
+        path: Option<PathBuf>,
+        mode: Mode,
+        compression: u16,
+        compresslevel: Option<u8>,
+        compatibility: Compatibility,
+        deflate_backend: DeflateBackend,
+    ) -> PyResult<Self> {
+        let file = match mode {
+            Mode::Read => ZipFileInner::Read(ReadZipFile::new(file, path)?),
+            Mode::Write => {
+                let compression_kind = CompressionKind::try_from(compression)?;
+
+                ZipFileInner::Write(WriteZipFile::new(
+                    file,
+                    compression_kind,
+                    compresslevel,
+                    compatibility,
+                    deflate_backend,
+                )?)
+            }
+            Mode::Append => {
+                let compression_kind = CompressionKind::try_from(compression)?;
+
+                ZipFileInner::Write(WriteZipFile::new_append(
+                    file,
+                    compression_kind,
+                    compresslevel,
+                    compatibility,
+                    deflate_backend,
+                )?)
+            }
+        };
+
+        Ok(Self {
+            file,
+            path_options: None,
+        })
+    }
 }
 
 #[pymethods]
 impl ZipFile {
     #[new]
-    #[pyo3(signature = (file, mode="r", compression=ZIP_STORED, allowZip64=true, compresslevel=None), text_signature = "(file, mode=\"r\", compression=ZIP_STORED, allowZip64=True, compressionlevel=None)")]
+    #[pyo3(signature = (file, mode="r", compression=ZIP_STORED, allowZip64=true, compresslevel=None, *, compatibility="modern", deflate_backend="zlib", read_timeout=None, require_plaintext=false, max_entry_size=None, mmap=false, prepend=None), text_signature = "(file, mode=\"r\", compression=ZIP_STORED, allowZip64=True, compressionlevel=None, *, compatibility=\"modern\", deflate_backend=\"zlib\", read_timeout=None, require_plaintext=False, max_entry_size=None, mmap=False, prepend=None)")]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         file: PyObject,
         mode: &str,
-        compression: u8,
+        compression: u16,
         // Follow original python api
         #[allow(non_snake_case)] allowZip64: bool,
         compresslevel: Option<u8>,
+        compatibility: &str,
+        deflate_backend: &str,
+        read_timeout: Option<f64>,
+        require_plaintext: bool,
+        max_entry_size: Option<u64>,
+        mmap: bool,
+        prepend: Option<Vec<u8>>,
         py: Python<'_>,
     ) -> PyResult<Self> {
-        if !allowZip64 {
-            return Err(PyNotImplementedError::new_err(
-                "allowZip64 must currently always be true",
-            ));
-        }
+        let compatibility = Compatibility::try_from(compatibility)?;
+        let deflate_backend = DeflateBackend::try_from(deflate_backend)?;
 
-        let file = match file.downcast_bound::<PyString>(py) {
-            Ok(file) => file.to_cow()?,
+        let mode = Mode::try_from(mode)?;
+        let path = match file.downcast_bound::<PyString>(py) {
+            Ok(file) => Some(PathBuf::from(&*file.to_cow()?)),
+            Err(_error) if PyFileLike::is_file_like(file.bind(py))? => None,
             Err(_error) => {
                 return Err(PyValueError::new_err(
-                    "ZipFile file currently must be a string",
+                    "ZipFile file must be a string or a file-like object",
                 ));
             }
         };
 
-        let file = match mode {
-            "r" => {
-                let file = File::open(&*file)?;
+        if path.is_some() && read_timeout.is_some() {
+            return Err(PyValueError::new_err(
+                "read_timeout is only supported for file-like objects",
+            ));
+        }
 
-                ZipFileInner::Read(ReadZipFile::new(file)?)
-            }
-            "w" => {
-                let file = File::create(&*file)?;
-                let compression_kind = CompressionKind::try_from(compression)?;
+        check_limits_mode(mode, require_plaintext, max_entry_size)?;
 
-                ZipFileInner::Write(WriteZipFile::new(file, compression_kind, compresslevel)?)
-            }
-            "x" | "a" => {
-                return Err(PyNotImplementedError::new_err(
-                    "ZipFile modes 'w', 'x', and 'a' are currently unsupported",
-                ));
-            }
-            _ => {
-                return Err(PyValueError::new_err(
-                    "ZipFile requires mode 'r', 'w', 'x', or 'a'",
-                ));
-            }
+        if mmap && (path.is_none() || mode != Mode::Read) {
+            return Err(PyValueError::new_err(
+                "mmap is only supported when reading from a path",
+            ));
+        }
+
+        // Bytes before the archive, like a self-extractor stub, are only written to new archives.
+        // Offsets in the archive are from the start of the file, so they account for the prefix.
+        if prepend.is_some() && mode != Mode::Write {
+            return Err(PyValueError::new_err(
+                "prepend is only supported when writing a new archive",
+            ));
+        }
+
+        if let Some(path) = path {
+            return Self::from_path(PathOptions {
+                path,
+                mode,
+                compression,
+                compresslevel,
+                compatibility,
+                deflate_backend,
+                require_plaintext,
+                max_entry_size,
+                allow_zip64: allowZip64,
+                mmap,
+                prepend,
+            });
+        }
+
+        let read_timeout = read_timeout
+            .map(|read_timeout| {
+                Duration::try_from_secs_f64(read_timeout)
+                    .ok()
+                    .filter(|read_timeout| !read_timeout.is_zero())
+                    .ok_or_else(|| {
+                        PyValueError::new_err(format!(
+                            "read_timeout must be a positive number of seconds, got {read_timeout}"
+                        ))
+                    })
+            })
+            .transpose()?;
+        let mut file = ArchiveFile::Python(PyFileLike::new(file).with_read_timeout(read_timeout));
+        if let Some(prepend) = prepend {
+            file.write_all(&prepend)?;
+        }
+
+        let mut zip_file = Self::from_archive_file(
+            file,
+            None,
+            mode,
+            compression,
+            compresslevel,
+            compatibility,
+            deflate_backend,
+        )?;
+        zip_file.apply_limits(require_plaintext, max_entry_size, allowZip64)?;
+
+        Ok(zip_file)
+    }
+
+    /// Open an archive from an already-open OS file descriptor.
+    ///
+    /// The ZipFile takes ownership of the descriptor, and closes it when the archive is closed.
+    /// The descriptor must not be used or closed by anything else afterwards.
+    /// On Windows, this takes a raw file handle, like one from `msvcrt.get_osfhandle`.
+    /// Other arguments are like the constructor's.
+    #[staticmethod]
+    #[pyo3(signature = (fd, mode="r", compression=ZIP_STORED, allowZip64=true, compresslevel=None, *, compatibility="modern", deflate_backend="zlib", require_plaintext=false, max_entry_size=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn from_fd(
+        fd: i64,
+        mode: &str,
+        compression: u16,
+        #[allow(non_snake_case)] allowZip64: bool,
+        compresslevel: Option<u8>,
+        compatibility: &str,
+        deflate_backend: &str,
+        require_plaintext: bool,
+        max_entry_size: Option<u64>,
+    ) -> PyResult<Self> {
+        let compatibility = Compatibility::try_from(compatibility)?;
+        let deflate_backend = DeflateBackend::try_from(deflate_backend)?;
+        let mode = Mode::try_from(mode)?;
+        check_limits_mode(mode, require_plaintext, max_entry_size)?;
+
+        #[cfg(unix)]
+        let mut file = {
+            use std::os::fd::FromRawFd;
+            use std::os::fd::RawFd;
+
+            let fd = RawFd::try_from(fd)
+                .ok()
+                .filter(|fd| *fd >= 0)
+                .ok_or_else(|| PyValueError::new_err(format!("{fd} is not a valid fd")))?;
+
+            // SAFETY: The caller passes ownership of the descriptor to us, as documented.
+            unsafe { File::from_raw_fd(fd) }
+        };
+
+        #[cfg(windows)]
+        let mut file = {
+            use std::os::windows::io::FromRawHandle;
+            use std::os::windows::io::RawHandle;
+
+            // Null and negative values are never handles we can own:
+            // -1 is `INVALID_HANDLE_VALUE`, and other negative values are pseudo-handles.
+            let handle = isize::try_from(fd)
+                .ok()
+                .filter(|handle| *handle > 0)
+                .ok_or_else(|| PyValueError::new_err(format!("{fd} is not a valid handle")))?;
+
+            // SAFETY: The caller passes ownership of the handle to us, as documented.
+            unsafe { File::from_raw_handle(handle as RawHandle) }
         };
 
-        Ok(Self { file })
+        if mode == Mode::Write {
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+        }
+
+        let mut zip_file = Self::from_archive_file(
+            ArchiveFile::Os(file),
+            None,
+            mode,
+            compression,
+            compresslevel,
+            compatibility,
+            deflate_backend,
+        )?;
+        zip_file.apply_limits(require_plaintext, max_entry_size, allowZip64)?;
+
+        Ok(zip_file)
     }
 
     /// Close the archive file.
+    ///
+    /// Closing an archive that was already closed does nothing, like closing a Python file.
+    /// An archive can't be closed while one of its members is open.
     pub fn close(&mut self) -> PyResult<()> {
         match &mut self.file {
             ZipFileInner::Read(file) => file.close(),
@@ -131,131 +602,1607 @@ impl ZipFile {
         }
     }
 
-    #[pyo3(signature = (name, mode="r", pwd=None))]
+    /// Close the archive, if it is open, and open its path again with the same options.
+    ///
+    /// Archives opened for writing are reopened for appending,
+    /// so that reopening doesn't truncate what was written.
+    /// Only archives opened from a path can be reopened.
+    pub fn reopen(&mut self) -> PyResult<()> {
+        let mut options = self.path_options.clone().ok_or_else(|| {
+            PyValueError::new_err("only archives opened from a path can be reopened")
+        })?;
+
+        self.close()?;
+        if options.mode == Mode::Write {
+            options.mode = Mode::Append;
+        }
+        *self = Self::from_path(options)?;
+
+        Ok(())
+    }
+
+    /// Set the password used for encrypted members when no `pwd` is given, or clear it with `None`.
+    #[pyo3(signature = (pwd))]
+    pub fn setpassword(&mut self, pwd: Option<Bound<'_, PyBytes>>) -> PyResult<()> {
+        match &mut self.file {
+            ZipFileInner::Read(file) => {
+                file.set_default_password(pwd.as_ref().map(|pwd| pwd.as_bytes()));
+                Ok(())
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Open a member of the archive.
+    ///
+    /// Like `zipfile`, a missing member raises `KeyError`.
+    /// If several members share a name, the last one in the central directory is opened.
+    /// The zip crate indexes members by name,
+    /// so such duplicates are listed once by `namelist`, at the position of the first one.
+    ///
+    /// `force_method` decompresses the member with the given compression method,
+    /// ignoring the one stored in its header.
+    /// This is an escape hatch for archives that mislabel their members,
+    /// and skips CRC checks.
+    ///
+    /// With `lazy`, the archive is not locked until the member is first read,
+    /// so other operations may use the archive in the meantime.
+    ///
+    /// `max_ratio` raises a `ZipBombError` as soon as the member decompresses
+    /// to more than that many times its compressed size.
+    /// `expected_size` raises a `BadZipFile` before reading
+    /// if the recorded uncompressed size of the member differs.
+    ///
+    /// When writing, `force_zip64` writes the member with Zip64 extensions,
+    /// which is needed if it will be larger than 2 GiB.
+    #[pyo3(signature = (name, mode="r", pwd=None, *, ignore_case=false, force_method=None, lazy=false, force_zip64=false, max_ratio=None, expected_size=None))]
+    #[allow(clippy::too_many_arguments)]
     pub fn open(
         &mut self,
         name: &Bound<'_, PyAny>,
         mode: &str,
         pwd: Option<Bound<'_, PyBytes>>,
+        ignore_case: bool,
+        force_method: Option<u16>,
+        lazy: bool,
+        force_zip64: bool,
+        max_ratio: Option<f64>,
+        expected_size: Option<u64>,
     ) -> PyResult<ZipExtFile> {
         match (&mut self.file, mode) {
             (ZipFileInner::Read(file), "r") => {
-                if let Ok(name) = name.downcast::<PyString>() {
-                    let name = name.to_cow()?;
-
-                    Ok(ZipExtFile {
-                        inner: ZipExtFileInner::Read(Box::new(file.open(&name, pwd)?)),
-                    })
-                } else {
-                    Err(PyNotImplementedError::new_err(
-                        "name must currently be a string",
-                    ))
+                let name = resolve_read_name(file, name, ignore_case)?;
+                let options = MemberOptions {
+                    force_method: force_method.map(CompressionKind::try_from).transpose()?,
+                    max_ratio: validate_max_ratio(max_ratio)?,
+                };
+                // Checked up front, so lazy handles fail on open too.
+                if let Some(expected_size) = expected_size {
+                    file.check_expected_size(&name, expected_size)?;
                 }
+                let file = if lazy {
+                    file.open_lazy(&name, pwd, options)?
+                } else {
+                    file.open_with_options(&name, pwd, options)?
+                };
+
+                Ok(ZipExtFile {
+                    name: file.name().into(),
+                    inner: ZipExtFileInner::Read(Box::new(file)),
+                })
             }
-            (ZipFileInner::Read(_file), "w") => {
-                Err(PyValueError::new_err("archive opened as read-only"))
-            }
-            (ZipFileInner::Write(_file), "r") => {
-                Err(PyValueError::new_err("archive opened as write-only"))
-            }
+            (ZipFileInner::Read(_file), "w") => Err(read_only_error()),
+            (ZipFileInner::Write(_file), "r") => Err(write_only_error()),
             (ZipFileInner::Write(file), "w") => {
+                if force_method.is_some() {
+                    return Err(PyValueError::new_err(
+                        "force_method is only supported when reading",
+                    ));
+                }
+
+                if max_ratio.is_some() {
+                    return Err(PyValueError::new_err(
+                        "max_ratio is only supported when reading",
+                    ));
+                }
+
+                if expected_size.is_some() {
+                    return Err(PyValueError::new_err(
+                        "expected_size is only supported when reading",
+                    ));
+                }
+
                 if pwd.is_some() {
                     return Err(PyNotImplementedError::new_err(
                         "writing encrypted files is currently not supported",
                     ));
                 }
 
+                let file = file.open(name, force_zip64)?;
+
                 Ok(ZipExtFile {
-                    inner: ZipExtFileInner::Write(file.open(name)?),
+                    name: file.name().into(),
+                    inner: ZipExtFileInner::Write(file),
                 })
             }
             _ => Err(PyValueError::new_err("open() requires mode \"r\" or \"w\"")),
         }
     }
 
-    pub fn namelist(&self) -> PyResult<Vec<String>> {
+    /// Open a member for reading, as a binary file object.
+    ///
+    /// This is a shorthand for `open(name, "r", pwd)`,
+    /// for passing a member straight into code that expects a file, like `json.load`.
+    #[pyo3(signature = (name, pwd=None))]
+    pub fn open_file(
+        &self,
+        name: &Bound<'_, PyAny>,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<ZipExtFile> {
         match &self.file {
-            ZipFileInner::Read(file) => file.namelist(),
-            ZipFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
-                "listing writable files is currently unsupported",
-            )),
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let file = file.open(&name, pwd)?;
+
+                Ok(ZipExtFile {
+                    name: file.name().into(),
+                    inner: ZipExtFileInner::Read(Box::new(file)),
+                })
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
         }
     }
 
-    pub fn __enter__<'p>(this: PyRef<'p, Self>, _py: Python<'p>) -> PyResult<PyRef<'p, Self>> {
-        Ok(this)
+    /// Decompress a member into memory, and return it as a seekable file.
+    ///
+    /// This is for code that needs to seek within compressed members,
+    /// like reading a zip nested in a zip.
+    /// The whole decompressed member is held in memory until the returned file is closed.
+    #[pyo3(signature = (name, pwd=None))]
+    pub fn open_seekable(
+        &self,
+        name: &Bound<'_, PyAny>,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<ZipSeekableFile> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let data = file.read(&name, pwd)?;
+
+                Ok(ZipSeekableFile {
+                    cursor: Some(Cursor::new(data)),
+                })
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
     }
 
-    pub fn __exit__(
-        &mut self,
-        _exc_type: PyObject,
-        _exc_value: PyObject,
-        _traceback: PyObject,
-    ) -> PyResult<()> {
-        self.close()?;
-        Ok(())
+    /// Read the whole decompressed contents of a member.
+    ///
+    /// Missing members raise `KeyError`, like with `open`.
+    /// With `salvage`, a member that fails to decompress is read again as if it was stored,
+    /// returning its raw bytes without checking the CRC.
+    /// This is best-effort, meant for recovering data from corrupt or mislabeled members.
+    #[pyo3(signature = (name, pwd=None, *, salvage=false))]
+    pub fn read(
+        &self,
+        name: &Bound<'_, PyAny>,
+        pwd: Option<Bound<'_, PyBytes>>,
+        salvage: bool,
+    ) -> PyResult<Vec<u8>> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                if salvage {
+                    file.read_salvage(&name, pwd)
+                } else {
+                    file.read(&name, pwd)
+                }
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
     }
-}
 
-enum ZipExtFileInner {
-    Read(Box<ReadZipExtFile>),
-    Write(WriteZipExtFile),
-}
+    /// Iterate over `(ZipInfo, bytes)` pairs of every member's raw compressed data,
+    /// in central directory order, without decompressing anything.
+    ///
+    /// The archive is locked while iterating,
+    /// so no members may be opened until the iterator is exhausted.
+    pub fn iter_raw(&self) -> PyResult<ZipRawIterator> {
+        match &self.file {
+            ZipFileInner::Read(file) => Ok(ZipRawIterator {
+                entries: file.iter_raw()?,
+            }),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
 
-#[pyclass]
-pub struct ZipExtFile {
-    inner: ZipExtFileInner,
-}
+    /// Iterate over the contents of a member in chunks of at most `chunk_size` bytes.
+    ///
+    /// If a `callback` is given, it is called with the total number of bytes read so far after each chunk.
+    /// An exception raised by the callback aborts the read.
+    /// `max_ratio` raises a `ZipBombError` like it does for `open`.
+    #[pyo3(signature = (name, chunk_size=65536, pwd=None, *, callback=None, max_ratio=None))]
+    pub fn iter_chunks(
+        &mut self,
+        name: &Bound<'_, PyAny>,
+        chunk_size: usize,
+        pwd: Option<Bound<'_, PyBytes>>,
+        callback: Option<PyObject>,
+        max_ratio: Option<f64>,
+    ) -> PyResult<ZipChunkIterator> {
+        if chunk_size == 0 {
+            return Err(PyValueError::new_err("chunk_size must be greater than 0"));
+        }
 
-#[pymethods]
-impl ZipExtFile {
-    pub fn read(&mut self) -> PyResult<Vec<u8>> {
-        match &mut self.inner {
-            ZipExtFileInner::Read(file) => file.read(),
-            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
-                "Attempted to read to a write-only ZipExtFile",
-            )),
+        match &mut self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let options = MemberOptions {
+                    max_ratio: validate_max_ratio(max_ratio)?,
+                    ..MemberOptions::default()
+                };
+
+                Ok(ZipChunkIterator {
+                    file: file.open_with_options(&name, pwd, options)?,
+                    chunk_size,
+                    callback,
+                    position: 0,
+                })
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
         }
     }
 
-    pub fn write(&mut self, buffer: &[u8]) -> PyResult<()> {
-        match &mut self.inner {
-            ZipExtFileInner::Read(_file) => Err(PyNotImplementedError::new_err(
-                "Attempted to write to a read-only ZipExtFile",
-            )),
-            ZipExtFileInner::Write(file) => file.write(buffer),
+    /// Write a member for each item of a dict of names to bytes or strings, in insertion order.
+    ///
+    /// Strings are encoded as UTF-8.
+    /// Members use the archive's default compression.
+    /// With `auto_store`, members whose contents look incompressible, like already-compressed data,
+    /// are stored instead, to avoid wasting time compressing them.
+    #[pyo3(signature = (mapping, *, auto_store=false))]
+    pub fn write_all(&self, mapping: &Bound<'_, PyDict>, auto_store: bool) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(_file) => Err(read_only_error()),
+            ZipFileInner::Write(file) => file.write_all(mapping, auto_store),
         }
     }
 
-    pub fn close(&mut self) {
-        match &mut self.inner {
-            ZipExtFileInner::Read(file) => file.close(),
-            ZipExtFileInner::Write(file) => file.close(),
+    /// Write a member from bytes or a string, which is encoded as UTF-8, in one call.
+    ///
+    /// `zinfo_or_arcname` is a name, which uses the archive's compression and the current time,
+    /// or a `ZipInfo`, which uses its own compression and timestamp.
+    /// `compress_type` and `compresslevel` override either.
+    ///
+    /// `override_crc` is a testing escape hatch, for building archives with corrupt members.
+    /// It is written as the CRC of the member in both of its headers, instead of the real one,
+    /// so reading the member back fails its CRC check.
+    #[pyo3(signature = (zinfo_or_arcname, data, compress_type=None, compresslevel=None, *, override_crc=None))]
+    pub fn writestr(
+        &self,
+        zinfo_or_arcname: &Bound<'_, PyAny>,
+        data: &Bound<'_, PyAny>,
+        compress_type: Option<u16>,
+        compresslevel: Option<u8>,
+        override_crc: Option<u32>,
+    ) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(_file) => Err(read_only_error()),
+            ZipFileInner::Write(file) => file.writestr(
+                zinfo_or_arcname,
+                data,
+                compress_type,
+                compresslevel,
+                override_crc,
+            ),
         }
     }
 
-    pub fn __enter__<'p>(this: PyRef<'p, Self>, _py: Python<'p>) -> PyResult<PyRef<'p, Self>> {
-        Ok(this)
+    /// Write a file from the filesystem as a member, named `arcname` or the file's name.
+    ///
+    /// The member takes the file's modification time, in local time, and its permissions.
+    /// `compress_type` and `compresslevel` override the archive's compression.
+    #[pyo3(signature = (filename, arcname=None, compress_type=None, compresslevel=None))]
+    pub fn write(
+        &self,
+        py: Python<'_>,
+        filename: PathBuf,
+        arcname: Option<&str>,
+        compress_type: Option<u16>,
+        compresslevel: Option<u8>,
+    ) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(_file) => Err(read_only_error()),
+            ZipFileInner::Write(file) => {
+                file.write(py, &filename, arcname, compress_type, compresslevel)
+            }
+        }
     }
 
-    pub fn __exit__(&mut self, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) {
-        match &mut self.inner {
-            ZipExtFileInner::Read(file) => file.__exit__(),
-            ZipExtFileInner::Write(file) => file.__exit__(),
+    /// Add a directory entry, named by a string or `ZipInfo`, with a Unix `mode`.
+    ///
+    /// A trailing `/` is added to the name if it is missing.
+    /// Like any member, the directory can't be added while a handle is open, or if it already exists.
+    #[pyo3(signature = (zinfo_or_directory_name, mode=0o777))]
+    pub fn mkdir(&self, zinfo_or_directory_name: &Bound<'_, PyAny>, mode: u32) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(_file) => Err(read_only_error()),
+            ZipFileInner::Write(file) => file.mkdir(zinfo_or_directory_name, mode),
         }
     }
-}
-
-#[pyclass]
-#[derive(Clone)]
-pub struct ZipInfo {
-    #[pyo3(get, set)]
-    pub filename: String,
-    #[pyo3(get, set)]
-    pub compress_type: u8,
-    #[pyo3(get, set)]
-    pub compress_level: Option<u8>,
-}
+
+    /// Write a new member from an iterable of bytes chunks, without buffering the whole member.
+    ///
+    /// As the size is not known up front, the member only uses Zip64 extensions if `force_zip64` is set,
+    /// like members written with `open`.
+    /// This is needed if it will be larger than 2 GiB.
+    /// With `auto_store`, the first chunk is sampled,
+    /// and the member is stored instead of compressed if it looks incompressible.
+    #[pyo3(signature = (name, chunks, *, force_zip64=false, auto_store=false))]
+    pub fn write_iter(
+        &self,
+        name: &Bound<'_, PyAny>,
+        chunks: &Bound<'_, PyAny>,
+        force_zip64: bool,
+        auto_store: bool,
+    ) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(_file) => Err(read_only_error()),
+            ZipFileInner::Write(file) => file.write_iter(name, chunks, force_zip64, auto_store),
+        }
+    }
+
+    /// Read every member for which `predicate(zip_info)` is true, as a dict of names to bytes.
+    ///
+    /// This takes a single pass over the archive,
+    /// which is cheaper than filtering `infolist` and reading each match.
+    /// The archive is locked while the predicate runs, so it must not use the archive.
+    pub fn read_matching<'py>(
+        &self,
+        predicate: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.read_matching(predicate),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read `length` bytes of a member's decompressed contents, starting at offset `start`.
+    ///
+    /// Fewer bytes are returned if the member ends first.
+    /// Stored members are seeked into directly,
+    /// while compressed members are decompressed and discarded up to `start`.
+    #[pyo3(signature = (name, start, length, pwd=None))]
+    pub fn read_range(
+        &self,
+        name: &Bound<'_, PyAny>,
+        start: u64,
+        length: u64,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<Vec<u8>> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                file.read_range(&name, start, length, pwd)
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read a member as text.
+    ///
+    /// `newline` follows the reading behavior of Python's `open`:
+    /// `None` translates `"\r\n"` and `"\r"` line endings to `"\n"`,
+    /// while any other legal value leaves line endings untranslated.
+    #[pyo3(signature = (name, pwd=None, *, encoding="utf-8", newline=None))]
+    pub fn read_text(
+        &self,
+        name: &Bound<'_, PyAny>,
+        pwd: Option<Bound<'_, PyBytes>>,
+        encoding: &str,
+        newline: Option<&str>,
+        py: Python<'_>,
+    ) -> PyResult<String> {
+        if let Some(newline) = newline {
+            if !matches!(newline, "" | "\n" | "\r" | "\r\n") {
+                return Err(PyValueError::new_err(format!(
+                    "illegal newline value: {newline:?}"
+                )));
+            }
+        }
+
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let data = file.read(&name, pwd)?;
+                let text: String = PyBytes::new(py, &data)
+                    .call_method1(intern!(py, "decode"), (encoding,))?
+                    .extract()?;
+
+                match newline {
+                    None => Ok(text.replace("\r\n", "\n").replace('\r', "\n")),
+                    Some(_) => Ok(text),
+                }
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read a member and parse it as JSON, with Python's `json.loads`.
+    ///
+    /// Malformed JSON raises a `ValueError` naming the member,
+    /// caused by the original error from `json`.
+    #[pyo3(signature = (name, pwd=None))]
+    pub fn read_json<'py>(
+        &self,
+        name: &Bound<'py, PyAny>,
+        pwd: Option<Bound<'py, PyBytes>>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let data = file.read(&name, pwd)?;
+
+                let json = py.import(intern!(py, "json"))?;
+                json.call_method1(intern!(py, "loads"), (PyBytes::new(py, &data),))
+                    .map_err(|error| {
+                        if !error.is_instance_of::<PyValueError>(py) {
+                            return error;
+                        }
+
+                        let new_error = PyValueError::new_err(format!(
+                            "File {name} is not valid JSON: {error}"
+                        ));
+                        new_error.set_cause(py, Some(error));
+                        new_error
+                    })
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read a member, checking that its SHA-256 digest matches `expected_sha256`.
+    ///
+    /// `expected_sha256` is a hex digest.
+    /// The member is hashed as it is read, and a `ValueError` is raised if the digest differs.
+    #[pyo3(signature = (name, expected_sha256, pwd=None))]
+    pub fn read_verified<'py>(
+        &self,
+        name: &Bound<'py, PyAny>,
+        expected_sha256: &str,
+        pwd: Option<Bound<'py, PyBytes>>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let ZipFileInner::Read(file) = &self.file else {
+            return Err(write_only_error());
+        };
+        let name = resolve_read_name(file, name, false)?;
+
+        let hasher = py
+            .import(intern!(py, "hashlib"))?
+            .call_method0(intern!(py, "sha256"))?;
+        let mut data = Vec::new();
+        file.hash(
+            &name,
+            pwd,
+            std::slice::from_ref(&hasher),
+            Some(&mut data),
+            py,
+        )?;
+
+        let digest: String = hasher.call_method0(intern!(py, "hexdigest"))?.extract()?;
+        if !digest.eq_ignore_ascii_case(expected_sha256.trim()) {
+            return Err(PyValueError::new_err(format!(
+                "File {name} has SHA-256 digest {digest}, expected {expected_sha256}"
+            )));
+        }
+
+        Ok(PyBytes::new(py, &data))
+    }
+
+    /// Compute several digests of a member in a single pass, as a dict of algorithm to hex digest.
+    ///
+    /// `algorithms` are names accepted by `hashlib.new`, like `"md5"` or `"sha256"`.
+    #[pyo3(signature = (name, algorithms, pwd=None))]
+    pub fn digests<'py>(
+        &self,
+        name: &Bound<'py, PyAny>,
+        algorithms: Vec<String>,
+        pwd: Option<Bound<'py, PyBytes>>,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let ZipFileInner::Read(file) = &self.file else {
+            return Err(write_only_error());
+        };
+        let name = resolve_read_name(file, name, false)?;
+
+        // Hashers are made before opening the member, so unknown algorithms fail early.
+        let hashlib = py.import(intern!(py, "hashlib"))?;
+        let hashers = algorithms
+            .iter()
+            .map(|algorithm| hashlib.call_method1(intern!(py, "new"), (algorithm,)))
+            .collect::<PyResult<Vec<_>>>()?;
+        file.hash(&name, pwd, &hashers, None, py)?;
+
+        let digests = PyDict::new(py);
+        for (algorithm, hasher) in algorithms.iter().zip(hashers) {
+            digests.set_item(algorithm, hasher.call_method0(intern!(py, "hexdigest"))?)?;
+        }
+
+        Ok(digests)
+    }
+
+    /// Read a member whose contents are an array of items `dtype_itemsize` bytes long.
+    ///
+    /// This raises a `ValueError` if the length of the member is not a multiple of `dtype_itemsize`,
+    /// so that the result can be safely passed to `numpy.frombuffer`.
+    #[pyo3(signature = (name, dtype_itemsize, pwd=None))]
+    pub fn read_into_array(
+        &self,
+        name: &Bound<'_, PyAny>,
+        dtype_itemsize: usize,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<Vec<u8>> {
+        if dtype_itemsize == 0 {
+            return Err(PyValueError::new_err(
+                "dtype_itemsize must be greater than 0",
+            ));
+        }
+
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                let data = file.read(&name, pwd)?;
+                let len = data.len();
+                if len % dtype_itemsize != 0 {
+                    return Err(PyValueError::new_err(format!(
+                        "File {name} is {len} bytes long, not a multiple of {dtype_itemsize}"
+                    )));
+                }
+
+                Ok(data)
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Extract a member into `path`, or the current directory, and return the extracted path.
+    ///
+    /// Like `extractall`, the member's name is sanitized so that it cannot escape `path`:
+    /// leading slashes, drive letters, and `..` components are dropped.
+    /// Missing parent directories are created.
+    ///
+    /// With `preserve_ownership`, the owner stored in the member's Info-ZIP Unix extra field is restored.
+    /// This only happens when running as root on Unix, and members without the field are left alone.
+    #[pyo3(signature = (member, path=None, pwd=None, *, preserve_ownership=false))]
+    pub fn extract(
+        &self,
+        member: &Bound<'_, PyAny>,
+        path: Option<PathBuf>,
+        pwd: Option<Bound<'_, PyBytes>>,
+        preserve_ownership: bool,
+    ) -> PyResult<OsString> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, member, false)?;
+                // Like `zipfile`, default to the absolute current directory,
+                // so that the returned path is absolute too.
+                let path = match path {
+                    Some(path) => path,
+                    None => std::env::current_dir()?,
+                };
+                let options = ExtractOptions {
+                    preserve_ownership,
+                    ..ExtractOptions::default()
+                };
+                let target = file.extract(
+                    &name,
+                    &path,
+                    pwd.as_ref().map(|pwd| pwd.as_bytes()),
+                    options,
+                )?;
+
+                Ok(target.into_os_string())
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Extract members into `path`, or the current directory.
+    ///
+    /// If `members`, a list of names or `ZipInfo`s, is not given, every member is extracted.
+    /// Member names are sanitized like with `extract`.
+    /// Other Python threads may run while extracting.
+    /// Extracted files get the modification time of their member, taken as local time,
+    /// like other unzip tools do.
+    /// With `skip_unchanged`, files that already exist with the member's size and modification time,
+    /// within the 2 second resolution of zip timestamps, are left alone.
+    /// This makes repeated extraction into the same directory cheap,
+    /// including over files extracted by other tools that set modification times.
+    ///
+    /// `max_total_size` raises a `ZipBombError` before more than that many bytes are written in total.
+    /// Members extracted up to then are kept, but the member that hit the limit is removed.
+    /// `max_files` raises a `ZipBombError` before anything is written
+    /// if more than that many members would be extracted.
+    ///
+    /// Extracting a file where a directory exists raises `IsADirectoryError`,
+    /// and extracting a directory where a file exists raises `NotADirectoryError`.
+    /// `preserve_ownership` restores owners like with `extract`.
+    #[pyo3(signature = (path=None, members=None, pwd=None, *, skip_unchanged=false, max_total_size=None, max_files=None, preserve_ownership=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn extractall(
+        &self,
+        path: Option<PathBuf>,
+        members: Option<Vec<Bound<'_, PyAny>>>,
+        pwd: Option<Bound<'_, PyBytes>>,
+        skip_unchanged: bool,
+        max_total_size: Option<u64>,
+        max_files: Option<usize>,
+        preserve_ownership: bool,
+        py: Python<'_>,
+    ) -> PyResult<()> {
+        let options = ExtractOptions {
+            skip_unchanged,
+            max_total_size,
+            max_files,
+            preserve_ownership,
+        };
+
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let members = members
+                    .map(|members| {
+                        members
+                            .iter()
+                            .map(|member| resolve_read_name(file, member, false))
+                            .collect::<PyResult<Vec<_>>>()
+                    })
+                    .transpose()?;
+
+                file.extractall(
+                    path.as_deref().unwrap_or(Path::new(".")),
+                    members.as_deref(),
+                    pwd.as_ref().map(|pwd| pwd.as_bytes()),
+                    options,
+                    py,
+                )
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read a member into `buf`, replacing its contents, and return the number of bytes read.
+    ///
+    /// Reusing the same bytearray across reads avoids allocating a new buffer for each member.
+    #[pyo3(signature = (name, buf, pwd=None))]
+    pub fn read_into_buf(
+        &self,
+        name: &Bound<'_, PyAny>,
+        buf: &Bound<'_, PyByteArray>,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<usize> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, false)?;
+                file.read_into_buf(&name, pwd, buf)
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Open a member for reading, returning its metadata alongside the handle.
+    #[pyo3(signature = (name, pwd=None, *, ignore_case=false))]
+    pub fn open_with_info(
+        &mut self,
+        name: &Bound<'_, PyAny>,
+        pwd: Option<Bound<'_, PyBytes>>,
+        ignore_case: bool,
+    ) -> PyResult<(ZipInfo, ZipExtFile)> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let name = resolve_read_name(file, name, ignore_case)?;
+                let mut file = file.open(&name, pwd)?;
+                let info = file.info()?;
+
+                Ok((
+                    info,
+                    ZipExtFile {
+                        name: file.name().into(),
+                        inner: ZipExtFileInner::Read(Box::new(file)),
+                    },
+                ))
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Return the names of every member, in central directory order.
+    ///
+    /// When writing, these are the members written so far, including any that is still open.
+    pub fn namelist(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.namelist(py),
+            ZipFileInner::Write(file) => Ok(file.namelist()),
+        }
+    }
+
+    /// Compare the archive against a directory, as if it were extracted there, without extracting.
+    ///
+    /// Returns a dict of member names that are `"missing"` from the directory,
+    /// files in the directory that are `"extra"`, named relative to it with `/` separators,
+    /// and member names whose files were `"changed"`, having a different size or CRC.
+    /// Directory members only need to exist, and are never changed.
+    pub fn diff_dir(
+        &self,
+        directory: PathBuf,
+        py: Python<'_>,
+    ) -> PyResult<BTreeMap<&'static str, Vec<String>>> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let diff = file.diff_dir(&directory, py)?;
+
+                Ok(BTreeMap::from([
+                    ("missing", diff.missing),
+                    ("extra", diff.extra),
+                    ("changed", diff.changed),
+                ]))
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Return the names of members modified after `timestamp`,
+    /// a Unix timestamp or a `datetime.datetime`, in central directory order.
+    ///
+    /// Member timestamps and naive datetimes are taken as local time, like `datetime.timestamp`.
+    /// Zip timestamps only have a 2 second resolution, rounded down,
+    /// so members modified up to 2 seconds before `timestamp` may be included.
+    /// When writing, this checks the members written so far.
+    pub fn modified_since(
+        &self,
+        timestamp: &Bound<'_, PyAny>,
+        py: Python<'_>,
+    ) -> PyResult<Vec<String>> {
+        let timestamp = if timestamp.hasattr(intern!(py, "tzinfo"))? {
+            timestamp
+                .call_method0(intern!(py, "timestamp"))?
+                .extract()?
+        } else {
+            timestamp.extract()?
+        };
+
+        match &self.file {
+            ZipFileInner::Read(file) => file.modified_since(timestamp, py),
+            ZipFileInner::Write(file) => listing::modified_since(py, &file.infolist(), timestamp),
+        }
+    }
+
+    /// Return the set of names at the root of the archive.
+    ///
+    /// Directories end with a slash, and are included even if they only appear in member paths,
+    /// so `"a/"` is listed for a member named `"a/b/c.txt"`.
+    /// When writing, this lists the members written so far.
+    pub fn toplevel(&self, py: Python<'_>) -> PyResult<BTreeSet<String>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.toplevel(py),
+            ZipFileInner::Write(file) => Ok(listing::toplevel(&file.infolist())),
+        }
+    }
+
+    /// Return the `n` largest members by uncompressed size, as `(name, size)` tuples.
+    ///
+    /// Members are sorted largest first, with members of the same size sorted by name.
+    /// When writing, this lists the members written so far.
+    #[pyo3(signature = (n=10))]
+    pub fn largest(&self, n: usize, py: Python<'_>) -> PyResult<Vec<(String, u64)>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.largest(n, py),
+            ZipFileInner::Write(file) => Ok(listing::largest(&file.infolist(), n)),
+        }
+    }
+
+    /// Return the total uncompressed size of members for each file extension.
+    ///
+    /// Extensions include the leading dot, like `os.path.splitext`,
+    /// and members without an extension are grouped under `""`.
+    /// Directories are not counted.
+    /// When writing, this counts the members written so far.
+    pub fn size_by_extension(&self, py: Python<'_>) -> PyResult<BTreeMap<String, u64>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.size_by_extension(py),
+            ZipFileInner::Write(file) => Ok(listing::size_by_extension(&file.infolist())),
+        }
+    }
+
+    /// Return the names of members that use a compression method this module can't read,
+    /// like PPMd or unknown methods.
+    ///
+    /// This is computed from the central directory, without reading any member.
+    /// Encryption is not reported, as both ZipCrypto and AES are supported.
+    /// When writing, this checks the members written so far.
+    pub fn unsupported_entries(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.unsupported_entries(py),
+            ZipFileInner::Write(file) => Ok(listing::unsupported_entries(&file.infolist())),
+        }
+    }
+
+    /// Return the `ZipInfo` of a member.
+    ///
+    /// Like `zipfile`, a missing member raises `KeyError`.
+    /// When writing, this looks up the members written so far.
+    pub fn getinfo(&self, name: &str) -> PyResult<ZipInfo> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.getinfo(name),
+            ZipFileInner::Write(file) => file.getinfo(name),
+        }
+    }
+
+    /// Return a `ZipInfo` for every member, in the same order as `namelist`.
+    ///
+    /// When writing, the member written last has a compressed size of zero until the next one is started,
+    /// or the archive is closed, as that is when it is finished.
+    pub fn infolist(&self, py: Python<'_>) -> PyResult<Vec<ZipInfo>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.infolist(py),
+            ZipFileInner::Write(file) => Ok(file.infolist()),
+        }
+    }
+
+    /// Print a table of members with their modification times and sizes, like `zipfile`.
+    ///
+    /// The table is printed to `file`, or `sys.stdout` if it is not given.
+    /// When writing, this lists the members written so far.
+    #[pyo3(signature = (file=None))]
+    pub fn printdir(&self, file: Option<&Bound<'_, PyAny>>, py: Python<'_>) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(zip_file) => zip_file.printdir(file, py),
+            ZipFileInner::Write(zip_file) => listing::printdir(py, &zip_file.infolist(), file),
+        }
+    }
+
+    /// Read every member and check its CRC, returning the name of the first corrupt member,
+    /// or `None` if every member is fine.
+    pub fn testzip(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.testzip(py),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Read every member and check its CRC, returning `(name, stored_crc, computed_crc, ok)`
+    /// for each member, instead of stopping at the first corrupt one like `testzip`.
+    pub fn audit(&self, py: Python<'_>) -> PyResult<Vec<(String, u32, u32, bool)>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.audit(py),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Return the names of members matching a shell-style glob pattern.
+    /// When writing, this matches the members written so far.
+    pub fn glob(&self, pattern: &str, py: Python<'_>) -> PyResult<Vec<String>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.glob(pattern, py),
+            ZipFileInner::Write(file) => Ok(listing::glob(&file.infolist(), pattern)),
+        }
+    }
+
+    /// Describe every member as a JSON string.
+    ///
+    /// The result is an array with an object per member, in central directory order,
+    /// with the `filename`, `compress_size`, `file_size`, `CRC`, `compress_type`,
+    /// and `date_time` of the member.
+    /// When writing, this describes the members written so far, like `infolist`.
+    pub fn metadata_json(&self, py: Python<'_>) -> PyResult<String> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.metadata_json(py),
+            ZipFileInner::Write(file) => listing::metadata_json(py, &file.infolist()),
+        }
+    }
+
+    /// Read and cache the central directory immediately.
+    ///
+    /// Metadata methods and opening a member otherwise read it on first use.
+    /// The central directory can't be read while the archive is in use,
+    /// like by a `stream_entries` or `iter_raw` iterator.
+    pub fn prefetch(&self) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.prefetch(),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Check if the archive or any member uses Zip64 extensions.
+    pub fn is_zip64(&self) -> PyResult<bool> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.is_zip64(),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Return the most common compression method among members,
+    /// or `None` if the archive is empty.
+    /// When writing, this counts the members written so far.
+    pub fn dominant_compression(&self) -> PyResult<Option<u16>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.dominant_compression(),
+            ZipFileInner::Write(file) => Ok(listing::dominant_compress_type(&file.infolist())),
+        }
+    }
+
+    /// Return the disk numbers recorded in the end of central directory record, as a dict.
+    ///
+    /// The dict has the `disk_number` of the disk holding the record,
+    /// the `disk_with_central_directory` where the central directory starts,
+    /// and, for Zip64 archives, the number of `total_disks` from the Zip64 locator.
+    /// Other archives don't record the number of disks, so it is left out.
+    /// A single-disk archive has zero for both disk numbers.
+    /// Spanned archives themselves are not supported, but this helps diagnose them.
+    pub fn disk_info(&self) -> PyResult<BTreeMap<&'static str, u32>> {
+        match &self.file {
+            ZipFileInner::Read(file) => {
+                let end_of_central_directory = file.end_of_central_directory();
+
+                let mut disk_info = BTreeMap::from([
+                    ("disk_number", end_of_central_directory.disk_number),
+                    (
+                        "disk_with_central_directory",
+                        end_of_central_directory.disk_with_central_directory,
+                    ),
+                ]);
+                if let Some(total_disks) = end_of_central_directory.total_disks {
+                    disk_info.insert("total_disks", total_disks);
+                }
+
+                Ok(disk_info)
+            }
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Guess the encoding of member names, for legacy archives without the UTF-8 flag.
+    ///
+    /// Returns a Python codec name and a confidence between 0 and 1.
+    /// Names that are ASCII or valid UTF-8 give `"ascii"` or `"utf-8"`.
+    /// Otherwise, a sample of names is decoded with common code pages,
+    /// and the one giving the most plausible letters is picked.
+    pub fn detect_encoding(&self, py: Python<'_>) -> PyResult<(String, f64)> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.detect_encoding(py),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Return the number of members using each compression method, keyed by `compress_type`.
+    /// When writing, this counts the members written so far.
+    pub fn method_histogram(&self) -> PyResult<BTreeMap<u16, usize>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.method_histogram(),
+            ZipFileInner::Write(file) => Ok(listing::compress_type_counts(&file.infolist())),
+        }
+    }
+
+    /// Write a copy of the archive to `output_path` where every member is stored uncompressed.
+    pub fn explode(&self, output_path: PathBuf) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.explode(&output_path),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Write a copy of the archive to `output_path` with its members sorted by name.
+    ///
+    /// Members are copied without recompression,
+    /// which makes for canonical archives that are easier to diff.
+    pub fn sorted_copy(&self, output_path: PathBuf) -> PyResult<()> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.sorted_copy(&output_path),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Convert the archive into a tar archive at `output_path`.
+    ///
+    /// `compression` may be `None`, `"gz"`, or `"bz2"`, like the modes of `tarfile`.
+    /// Members are streamed, keeping their names, sizes, permissions, and modification times.
+    #[pyo3(signature = (output_path, compression=None))]
+    pub fn to_tar(
+        &self,
+        py: Python<'_>,
+        output_path: PathBuf,
+        compression: Option<&str>,
+    ) -> PyResult<()> {
+        let compression = TarCompression::try_from(compression)?;
+
+        match &self.file {
+            ZipFileInner::Read(file) => file.to_tar(py, &output_path, compression),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Split the archive into volumes of at most `max_volume_size` bytes.
+    ///
+    /// Returns the paths of the written volumes.
+    pub fn split(&self, max_volume_size: u64) -> PyResult<Vec<PathBuf>> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.split(max_volume_size),
+            ZipFileInner::Write(_file) => Err(write_only_error()),
+        }
+    }
+
+    /// Get the number of members, like `len(namelist())`.
+    pub fn __len__(&self) -> PyResult<usize> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.len(),
+            ZipFileInner::Write(file) => Ok(file.len()),
+        }
+    }
+
+    /// Iterate over member names in central directory order, like `namelist()`,
+    /// without building the whole list.
+    pub fn __iter__(this: Bound<'_, Self>) -> PyResult<ZipNameIterator> {
+        // Fail early if the archive is closed.
+        if let ZipFileInner::Read(file) = &this.borrow().file {
+            file.len()?;
+        }
+
+        Ok(ZipNameIterator {
+            zip_file: this.unbind(),
+            index: 0,
+        })
+    }
+
+    /// Iterate over `(ZipInfo, ZipExtFile)` pairs for every member, in central directory order.
+    ///
+    /// Each member is only opened when the iterator reaches it,
+    /// and the previous member is closed when the iterator advances,
+    /// so only one member is open at a time.
+    /// Encrypted members are decrypted with `pwd`, or the default password if it is not given.
+    #[pyo3(signature = (pwd=None))]
+    pub fn stream_entries(
+        this: Bound<'_, Self>,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<ZipEntryIterator> {
+        match &this.borrow().file {
+            ZipFileInner::Read(file) => {
+                // Fail early if the archive is closed.
+                file.len()?;
+            }
+            ZipFileInner::Write(_file) => {
+                return Err(write_only_error());
+            }
+        }
+
+        Ok(ZipEntryIterator {
+            zip_file: this.unbind(),
+            pwd: pwd.map(Bound::unbind),
+            index: 0,
+            current: None,
+        })
+    }
+
+    /// Check if the archive has a member with the given name, for `name in zip_file`.
+    ///
+    /// When writing, this checks the members written so far.
+    pub fn __contains__(&self, name: &str) -> PyResult<bool> {
+        match &self.file {
+            ZipFileInner::Read(file) => file.contains(name),
+            ZipFileInner::Write(file) => Ok(file.contains(name)),
+        }
+    }
+
+    pub fn __enter__<'p>(this: PyRef<'p, Self>, _py: Python<'p>) -> PyResult<PyRef<'p, Self>> {
+        Ok(this)
+    }
+
+    pub fn __exit__(
+        &mut self,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<()> {
+        self.close()?;
+        Ok(())
+    }
+}
+
+/// Get the member name to open for reading from a name argument, a string or a `ZipInfo`.
+fn resolve_read_name(
+    file: &ReadZipFile,
+    name: &Bound<'_, PyAny>,
+    ignore_case: bool,
+) -> PyResult<String> {
+    let name = if let Ok(name) = name.downcast::<PyString>() {
+        name.to_cow()?.into_owned()
+    } else if let Ok(zip_info) = name.extract::<PyRef<'_, ZipInfo>>() {
+        zip_info.filename.clone()
+    } else {
+        return Err(PyValueError::new_err("name must be a string or ZipInfo"));
+    };
+
+    if ignore_case {
+        file.resolve_name_ignore_case(&name)
+    } else {
+        Ok(name)
+    }
+}
+
+enum ZipExtFileInner {
+    Read(Box<ReadZipExtFile>),
+    Write(WriteZipExtFile),
+}
+
+#[pyclass]
+pub struct ZipExtFile {
+    /// The name of the member.
+    #[pyo3(get)]
+    name: String,
+    inner: ZipExtFileInner,
+}
+
+#[pymethods]
+impl ZipExtFile {
+    /// Read up to `size` bytes, or the rest of the member if `size` is negative or `None`.
+    ///
+    /// Returns empty bytes at EOF.
+    #[pyo3(signature = (size=-1))]
+    pub fn read(&mut self, size: Option<i64>) -> PyResult<Vec<u8>> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => match size.and_then(|size| usize::try_from(size).ok()) {
+                Some(size) => file.read_chunk(size),
+                None => file.read(),
+            },
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to read to a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    /// Read up to and including the next `b"\n"`, or at most `size` bytes if it is not negative.
+    ///
+    /// Returns empty bytes at EOF.
+    #[pyo3(signature = (size=-1))]
+    pub fn readline(&mut self, size: Option<i64>) -> PyResult<Vec<u8>> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => {
+                file.readline(size.and_then(|size| usize::try_from(size).ok()))
+            }
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to read to a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    /// Read into a writable bytes-like object, like a `bytearray` or `memoryview`,
+    /// until it is full or EOF is reached, and return the number of bytes read.
+    ///
+    /// `bytearray`s are filled without allocating.
+    /// Other objects are filled through a `memoryview`, one chunk at a time.
+    pub fn readinto(&mut self, buffer: &Bound<'_, PyAny>) -> PyResult<usize> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.readinto(buffer),
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to read to a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    /// Whether the file was closed.
+    #[getter]
+    pub fn closed(&self) -> bool {
+        match &self.inner {
+            ZipExtFileInner::Read(file) => file.closed(),
+            ZipExtFileInner::Write(file) => file.closed(),
+        }
+    }
+
+    /// Do nothing, as data is handed to the archive as it is written.
+    ///
+    /// This exists so the file can be wrapped with `io.TextIOWrapper`.
+    pub fn flush(&self) -> PyResult<()> {
+        if self.closed() {
+            return Err(PyValueError::new_err(
+                "Attempt to use ZipExtFile that was already closed",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check if the file can be read from, which is the case for members opened for reading.
+    pub fn readable(&self) -> bool {
+        matches!(self.inner, ZipExtFileInner::Read(_))
+    }
+
+    /// Check if the file can be written to, which is the case for members opened for writing.
+    pub fn writable(&self) -> bool {
+        matches!(self.inner, ZipExtFileInner::Write(_))
+    }
+
+    /// Check if the file supports `seek`, which every member opened for reading does.
+    pub fn seekable(&self) -> bool {
+        matches!(self.inner, ZipExtFileInner::Read(_))
+    }
+
+    /// Return the current position in the decompressed member, or the bytes written so far.
+    pub fn tell(&mut self) -> PyResult<u64> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.tell(),
+            ZipExtFileInner::Write(file) => Ok(file.tell()),
+        }
+    }
+
+    /// Move to a new position in the decompressed member, and return it.
+    ///
+    /// `whence` is 0 to seek from the start, 1 from the current position, or 2 from the end.
+    /// Compressed data can't be seeked into, so seeking forwards reads up to the position,
+    /// and seeking backwards reopens the member and reads it from the start.
+    /// Like `zipfile`, seeking past the end stops at the end.
+    #[pyo3(signature = (offset, whence=0))]
+    pub fn seek(&mut self, offset: i64, whence: u8) -> PyResult<u64> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.seek(offset, whence),
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to seek a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    /// Return upcoming bytes without consuming them.
+    ///
+    /// Like `io.BufferedReader.peek`, the returned bytes may be more or fewer than `n`.
+    /// Fewer are only returned at EOF, or if `n` is more than the 8 KiB read-ahead buffer.
+    #[pyo3(signature = (n=1))]
+    pub fn peek(&mut self, n: i64) -> PyResult<Vec<u8>> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.peek(usize::try_from(n).unwrap_or(0)),
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to read to a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    /// Read lines until EOF, or until the lines read total at least `hint` bytes,
+    /// if it is positive.
+    #[pyo3(signature = (hint=-1))]
+    pub fn readlines<'py>(
+        &mut self,
+        hint: Option<i64>,
+        py: Python<'py>,
+    ) -> PyResult<Vec<Bound<'py, PyBytes>>> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => {
+                let hint = hint
+                    .filter(|hint| *hint > 0)
+                    .and_then(|hint| usize::try_from(hint).ok());
+                let lines = file.readlines(hint)?;
+
+                Ok(lines.iter().map(|line| PyBytes::new(py, line)).collect())
+            }
+            ZipExtFileInner::Write(_file) => Err(PyNotImplementedError::new_err(
+                "Attempted to read to a write-only ZipExtFile",
+            )),
+        }
+    }
+
+    pub fn write(&mut self, buffer: &[u8]) -> PyResult<()> {
+        match &mut self.inner {
+            ZipExtFileInner::Read(_file) => {
+                Err(PyValueError::new_err("ZipExtFile opened as read-only"))
+            }
+            ZipExtFileInner::Write(file) => file.write(buffer),
+        }
+    }
+
+    /// Close the member, which does nothing if it was already closed.
+    pub fn close(&mut self) {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.close(),
+            ZipExtFileInner::Write(file) => file.close(),
+        }
+    }
+
+    pub fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    /// Read the next line, for iterating over the lines of a member.
+    pub fn __next__(&mut self) -> PyResult<Option<Vec<u8>>> {
+        let line = self.readline(None)?;
+
+        Ok((!line.is_empty()).then_some(line))
+    }
+
+    pub fn __enter__<'p>(this: PyRef<'p, Self>, _py: Python<'p>) -> PyResult<PyRef<'p, Self>> {
+        Ok(this)
+    }
+
+    pub fn __exit__(&mut self, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) {
+        match &mut self.inner {
+            ZipExtFileInner::Read(file) => file.__exit__(),
+            ZipExtFileInner::Write(file) => file.__exit__(),
+        }
+    }
+}
+
+/// A member decompressed into memory, which can be seeked freely.
+#[pyclass]
+pub struct ZipSeekableFile {
+    cursor: Option<Cursor<Vec<u8>>>,
+}
+
+impl ZipSeekableFile {
+    fn cursor_mut(&mut self) -> PyResult<&mut Cursor<Vec<u8>>> {
+        self.cursor
+            .as_mut()
+            .ok_or_else(|| PyValueError::new_err("I/O operation on closed file."))
+    }
+}
+
+#[pymethods]
+impl ZipSeekableFile {
+    /// Read up to `size` bytes, or the rest of the member if `size` is negative or `None`.
+    #[pyo3(signature = (size=-1))]
+    pub fn read(&mut self, size: Option<i64>) -> PyResult<Vec<u8>> {
+        let cursor = self.cursor_mut()?;
+
+        let mut buffer = Vec::new();
+        match size.and_then(|size| u64::try_from(size).ok()) {
+            Some(size) => cursor.take(size).read_to_end(&mut buffer)?,
+            None => cursor.read_to_end(&mut buffer)?,
+        };
+
+        Ok(buffer)
+    }
+
+    /// Move to a new position, relative to the start for `whence=0`,
+    /// the current position for `whence=1`, or the end for `whence=2`.
+    ///
+    /// Returns the new position.
+    #[pyo3(signature = (offset, whence=0))]
+    pub fn seek(&mut self, offset: i64, whence: u8) -> PyResult<u64> {
+        let position = match whence {
+            0 => SeekFrom::Start(u64::try_from(offset).map_err(|_error| {
+                PyValueError::new_err(format!("negative seek position {offset}"))
+            })?),
+            1 => SeekFrom::Current(offset),
+            2 => SeekFrom::End(offset),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "invalid whence ({whence}, should be 0, 1 or 2)"
+                )));
+            }
+        };
+
+        Ok(self.cursor_mut()?.seek(position)?)
+    }
+
+    pub fn tell(&mut self) -> PyResult<u64> {
+        Ok(self.cursor_mut()?.position())
+    }
+
+    pub fn readable(&self) -> bool {
+        true
+    }
+
+    pub fn seekable(&self) -> bool {
+        true
+    }
+
+    pub fn writable(&self) -> bool {
+        false
+    }
+
+    pub fn close(&mut self) {
+        self.cursor.take();
+    }
+
+    pub fn __enter__<'p>(this: PyRef<'p, Self>, _py: Python<'p>) -> PyResult<PyRef<'p, Self>> {
+        Ok(this)
+    }
+
+    pub fn __exit__(&mut self, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) {
+        self.close();
+    }
+}
+
+/// An iterator over the contents of a member, in chunks.
+#[pyclass]
+pub struct ZipChunkIterator {
+    file: ReadZipExtFile,
+    chunk_size: usize,
+    callback: Option<PyObject>,
+    position: u64,
+}
+
+#[pymethods]
+impl ZipChunkIterator {
+    pub fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    pub fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        let chunk = match self.file.read_chunk(self.chunk_size) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                self.file.close();
+                return Err(error);
+            }
+        };
+
+        // Release the archive as soon as the member is exhausted.
+        if chunk.is_empty() {
+            self.file.close();
+            return Ok(None);
+        }
+
+        self.position += chunk.len() as u64;
+        if let Some(callback) = self.callback.as_ref() {
+            if let Err(error) = callback.call1(py, (self.position,)) {
+                self.file.close();
+                return Err(error);
+            }
+        }
+
+        Ok(Some(PyBytes::new(py, &chunk)))
+    }
+}
+
+/// An iterator over the member names of an archive.
+#[pyclass]
+pub struct ZipNameIterator {
+    zip_file: Py<ZipFile>,
+    index: usize,
+}
+
+#[pymethods]
+impl ZipNameIterator {
+    pub fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    pub fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<String>> {
+        let zip_file = self.zip_file.borrow(py);
+        let name = match &zip_file.file {
+            ZipFileInner::Read(file) => file.name_at(self.index)?,
+            ZipFileInner::Write(file) => file.name_at(self.index),
+        };
+        if name.is_some() {
+            self.index += 1;
+        }
+
+        Ok(name)
+    }
+}
+
+/// An iterator over the members of an archive, opening each in turn.
+#[pyclass]
+pub struct ZipEntryIterator {
+    zip_file: Py<ZipFile>,
+    /// The password to open members with.
+    pwd: Option<Py<PyBytes>>,
+    index: usize,
+    /// The last member that was yielded, which is closed before opening the next.
+    current: Option<Py<ZipExtFile>>,
+}
+
+#[pymethods]
+impl ZipEntryIterator {
+    pub fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    pub fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(ZipInfo, Py<ZipExtFile>)>> {
+        if let Some(current) = self.current.take() {
+            current.borrow_mut(py).close();
+        }
+
+        let zip_file = self.zip_file.borrow(py);
+        let ZipFileInner::Read(file) = &zip_file.file else {
+            return Ok(None);
+        };
+
+        let Some(name) = file.name_at(self.index)? else {
+            return Ok(None);
+        };
+        self.index += 1;
+
+        let pwd = self.pwd.as_ref().map(|pwd| pwd.bind(py).clone());
+        let mut file = file.open(&name, pwd)?;
+        let info = file.info()?;
+        let file = Py::new(
+            py,
+            ZipExtFile {
+                name: file.name().into(),
+                inner: ZipExtFileInner::Read(Box::new(file)),
+            },
+        )?;
+        self.current = Some(file.clone_ref(py));
+
+        Ok(Some((info, file)))
+    }
+}
+
+/// An iterator over the raw compressed data of the members of an archive.
+#[pyclass]
+pub struct ZipRawIterator {
+    entries: RawEntries,
+}
+
+#[pymethods]
+impl ZipRawIterator {
+    pub fn __iter__(this: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        this
+    }
+
+    pub fn __next__<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<Option<(ZipInfo, Bound<'py, PyBytes>)>> {
+        match self.entries.next_entry() {
+            Ok(entry) => Ok(entry.map(|(info, data)| (info, PyBytes::new(py, &data)))),
+            Err(error) => {
+                self.entries.close();
+                Err(error)
+            }
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ZipInfo {
+    #[pyo3(get, set)]
+    pub filename: String,
+    #[pyo3(get, set)]
+    pub compress_type: u16,
+    #[pyo3(get, set)]
+    pub compress_level: Option<u8>,
+    #[pyo3(get, set)]
+    pub date_time: DateTimeTuple,
+    // The metadata below is read from archives, or recorded while writing them,
+    // and is zero for new members.
+    #[pyo3(get)]
+    pub compress_size: u64,
+    #[pyo3(get)]
+    pub file_size: u64,
+    #[pyo3(get, name = "CRC")]
+    pub crc: u32,
+    #[pyo3(get)]
+    pub header_offset: u64,
+}
+
+impl ZipInfo {
+    /// Get the metadata of a member as it is stored in the archive.
+    pub(crate) fn from_zip_file<R: Read>(file: &zip::read::ZipFile<'_, R>) -> Self {
+        Self {
+            filename: file.name().into(),
+            compress_type: compress_type(file.compression()),
+            compress_level: None,
+            date_time: date_time_tuple(file.last_modified()),
+            compress_size: file.compressed_size(),
+            file_size: file.size(),
+            crc: file.crc32(),
+            header_offset: file.header_start(),
+        }
+    }
+
+    /// Get the metadata of a member from its central directory entry.
+    pub(crate) fn from_entry(entry: &CentralDirectoryEntry) -> Self {
+        Self {
+            filename: entry.name.clone(),
+            compress_type: entry.compress_type,
+            compress_level: None,
+            date_time: entry.date_time,
+            compress_size: entry.compress_size,
+            file_size: entry.file_size,
+            crc: entry.crc,
+            header_offset: entry.header_offset,
+        }
+    }
+}
 
 #[pymethods]
 impl ZipInfo {
@@ -266,8 +2213,105 @@ impl ZipInfo {
             filename: filename.into(),
             compress_type: ZIP_STORED,
             compress_level: None,
+            date_time: DEFAULT_DATE_TIME,
+            compress_size: 0,
+            file_size: 0,
+            crc: 0,
+            header_offset: 0,
         }
     }
+
+    /// Make a `ZipInfo` for in-memory data, with its size and CRC,
+    /// and a modification time of now, in local time.
+    #[staticmethod]
+    pub fn from_bytes(arcname: &str, data: &[u8], py: Python<'_>) -> PyResult<Self> {
+        let mut crc = Crc::new();
+        crc.update(data);
+
+        Ok(Self {
+            date_time: date_time_tuple(Some(local_time::now(py)?)),
+            file_size: data.len() as u64,
+            crc: crc.sum(),
+            ..Self::new(arcname)
+        })
+    }
+
+    /// Get the modification time as a `datetime.datetime`.
+    ///
+    /// Seconds are rounded down to the two-second resolution of zip timestamps,
+    /// and invalid zero months and days are treated as 1.
+    pub fn mtime_datetime<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let (year, month, day, hour, minute, second) = self.date_time;
+
+        let datetime = py.import(intern!(py, "datetime"))?;
+        datetime.getattr(intern!(py, "datetime"))?.call1((
+            year,
+            month.max(1),
+            day.max(1),
+            hour,
+            minute,
+            second - second % 2,
+        ))
+    }
+}
+
+/// Check if two archives have the same members.
+///
+/// With `compare="content"`, members must have identical decompressed contents.
+/// With `compare="metadata"`, members must have identical compression methods, timestamps,
+/// CRCs, sizes, permissions, and comments.
+/// Member order is not compared.
+#[pyfunction]
+#[pyo3(signature = (path_a, path_b, compare="content"))]
+fn archives_equal(
+    path_a: PathBuf,
+    path_b: PathBuf,
+    compare: &str,
+    py: Python<'_>,
+) -> PyResult<bool> {
+    let comparison = Comparison::try_from(compare)?;
+
+    py.allow_threads(|| self::compare::archives_equal(&path_a, &path_b, comparison))
+}
+
+/// Check if a path or file-like object is a zip archive, by trying to read its central directory.
+///
+/// Failing to open, seek, or read the file returns `False` instead of raising,
+/// as does an object that is neither a path nor a file-like object.
+#[pyfunction]
+fn is_zipfile(filename: &Bound<'_, PyAny>) -> PyResult<bool> {
+    let file = if PyFileLike::is_file_like(filename).unwrap_or(false) {
+        // Errors from `read` and `seek` surface as I/O errors while reading the archive.
+        ArchiveFile::Python(PyFileLike::new(filename.clone().unbind()))
+    } else {
+        let Ok(path) = filename.extract::<PathBuf>() else {
+            return Ok(false);
+        };
+        match File::open(path) {
+            Ok(file) => ArchiveFile::Os(file),
+            Err(_error) => return Ok(false),
+        }
+    };
+
+    Ok(ZipArchive::new(file).is_ok())
+}
+
+/// Compress data in memory and return its compressed size, without writing an archive.
+///
+/// `data` may be a bytes-like object or an iterable of bytes-like chunks.
+/// The compressed data is discarded as it is produced, so large inputs can be streamed.
+#[pyfunction]
+#[pyo3(signature = (data, compress_type, compresslevel=None))]
+fn estimate_compressed_size(
+    data: &Bound<'_, PyAny>,
+    compress_type: u16,
+    compresslevel: Option<u8>,
+) -> PyResult<u64> {
+    let compression_kind = CompressionKind::try_from(compress_type)?;
+
+    let mut estimator = Estimator::new(compression_kind, compresslevel)?;
+    estimator.feed(data)?;
+    estimator.finish()
 }
 
 #[pymodule]
@@ -275,10 +2319,22 @@ impl ZipInfo {
 fn nd_zipfile(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("ZIP_STORED", ZIP_STORED)?;
     m.add("ZIP_DEFLATED", ZIP_DEFLATED)?;
+    m.add("ZIP_DEFLATED64", ZIP_DEFLATED64)?;
     m.add("ZIP_BZIP2", ZIP_BZIP2)?;
     m.add("ZIP_LZMA", ZIP_LZMA)?;
+    m.add("BadZipFile", m.py().get_type::<BadZipFile>())?;
+    m.add("ZipBombError", m.py().get_type::<ZipBombError>())?;
+    m.add("LargeZipFile", m.py().get_type::<LargeZipFile>())?;
     m.add_class::<ZipFile>()?;
     m.add_class::<ZipInfo>()?;
     m.add_class::<ZipExtFile>()?;
+    m.add_class::<ZipSeekableFile>()?;
+    m.add_class::<ZipChunkIterator>()?;
+    m.add_class::<ZipRawIterator>()?;
+    m.add_class::<ZipNameIterator>()?;
+    m.add_class::<ZipEntryIterator>()?;
+    m.add_function(wrap_pyfunction!(archives_equal, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_compressed_size, m)?)?;
+    m.add_function(wrap_pyfunction!(is_zipfile, m)?)?;
     Ok(())
 }
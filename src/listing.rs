@@ -0,0 +1,262 @@
+use crate::central_directory::CentralDirectoryEntry;
+use crate::glob::glob_match;
+use crate::local_time;
+use crate::DateTimeTuple;
+use crate::ZipInfo;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::types::PyList;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use zip::CompressionMethod;
+
+/// The metadata of a member that listings are made from.
+///
+/// This is implemented by central directory entries of archives being read,
+/// and by the `ZipInfo` of members written so far to archives being written,
+/// so that both can be listed the same way.
+pub(crate) trait MemberMetadata {
+    fn name(&self) -> &str;
+    fn compress_type(&self) -> u16;
+    fn compress_size(&self) -> u64;
+    fn file_size(&self) -> u64;
+    fn crc(&self) -> u32;
+    fn date_time(&self) -> DateTimeTuple;
+    /// Whether the compression method of the member can be decompressed.
+    fn supported(&self) -> bool;
+}
+
+impl MemberMetadata for CentralDirectoryEntry {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn compress_type(&self) -> u16 {
+        self.compress_type
+    }
+
+    fn compress_size(&self) -> u64 {
+        self.compress_size
+    }
+
+    fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    fn date_time(&self) -> DateTimeTuple {
+        self.date_time
+    }
+
+    fn supported(&self) -> bool {
+        self.supported
+    }
+}
+
+impl MemberMetadata for ZipInfo {
+    fn name(&self) -> &str {
+        &self.filename
+    }
+
+    fn compress_type(&self) -> u16 {
+        self.compress_type
+    }
+
+    fn compress_size(&self) -> u64 {
+        self.compress_size
+    }
+
+    fn file_size(&self) -> u64 {
+        self.file_size
+    }
+
+    fn crc(&self) -> u32 {
+        self.crc
+    }
+
+    fn date_time(&self) -> DateTimeTuple {
+        self.date_time
+    }
+
+    fn supported(&self) -> bool {
+        #[allow(deprecated)]
+        !matches!(
+            CompressionMethod::from_u16(self.compress_type),
+            CompressionMethod::Unsupported(_)
+        )
+    }
+}
+
+/// Get the names of members matching a shell-style glob pattern.
+pub(crate) fn glob(members: &[impl MemberMetadata], pattern: &str) -> Vec<String> {
+    members
+        .iter()
+        .filter(|member| glob_match(pattern, member.name()))
+        .map(|member| member.name().into())
+        .collect()
+}
+
+/// Get the names of members modified after a Unix timestamp, with member timestamps taken as local time.
+///
+/// Zip timestamps are rounded down to 2 seconds,
+/// so members are included if they may have been modified after the timestamp.
+/// Members with invalid timestamps are never included.
+pub(crate) fn modified_since(
+    py: Python<'_>,
+    members: &[impl MemberMetadata],
+    timestamp: f64,
+) -> PyResult<Vec<String>> {
+    let mut names = Vec::new();
+    for member in members {
+        let modified = local_time::unix_timestamp(py, member.date_time())?;
+        if modified.is_some_and(|modified| modified as f64 + 2.0 > timestamp) {
+            names.push(member.name().into());
+        }
+    }
+
+    Ok(names)
+}
+
+/// Get the names of the files and directories at the root of the archive.
+///
+/// Directories are named with a trailing slash, and include those only implied by member names.
+pub(crate) fn toplevel(members: &[impl MemberMetadata]) -> BTreeSet<String> {
+    members
+        .iter()
+        .filter_map(|member| {
+            let name = member.name().trim_start_matches('/');
+            match name.split_once('/') {
+                Some((directory, _rest)) => Some(format!("{directory}/")),
+                None if name.is_empty() => None,
+                None => Some(name.into()),
+            }
+        })
+        .collect()
+}
+
+/// Get the `n` largest members by uncompressed size, largest first.
+///
+/// Members of the same size are ordered by name.
+pub(crate) fn largest(members: &[impl MemberMetadata], n: usize) -> Vec<(String, u64)> {
+    let mut members: Vec<_> = members.iter().collect();
+    members.sort_by(|a, b| {
+        b.file_size()
+            .cmp(&a.file_size())
+            .then_with(|| a.name().cmp(b.name()))
+    });
+
+    members
+        .into_iter()
+        .take(n)
+        .map(|member| (member.name().into(), member.file_size()))
+        .collect()
+}
+
+/// Get the total uncompressed size of members, grouped by file extension.
+///
+/// Directories are skipped.
+pub(crate) fn size_by_extension(members: &[impl MemberMetadata]) -> BTreeMap<String, u64> {
+    let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+    for member in members {
+        if member.name().ends_with('/') {
+            continue;
+        }
+
+        *sizes.entry(extension(member.name()).into()).or_default() += member.file_size();
+    }
+
+    sizes
+}
+
+/// Get the names of members that can't be read, in central directory order.
+pub(crate) fn unsupported_entries(members: &[impl MemberMetadata]) -> Vec<String> {
+    members
+        .iter()
+        .filter(|member| !member.supported())
+        .map(|member| member.name().into())
+        .collect()
+}
+
+/// Print a table of members, like `zipfile`, to a file or to `sys.stdout` if `None`.
+pub(crate) fn printdir(
+    py: Python<'_>,
+    members: &[impl MemberMetadata],
+    file: Option<&Bound<'_, PyAny>>,
+) -> PyResult<()> {
+    let print = py
+        .import(intern!(py, "builtins"))?
+        .getattr(intern!(py, "print"))?;
+    let kwargs = PyDict::new(py);
+    kwargs.set_item(intern!(py, "file"), file)?;
+
+    let header = format!("{:<46} {:>19} {:>12}", "File Name", "Modified    ", "Size");
+    print.call((header,), Some(&kwargs))?;
+    for member in members {
+        let (year, month, day, hour, minute, second) = member.date_time();
+        let line = format!(
+            "{:<46} {year}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02} {:>12}",
+            member.name(),
+            member.file_size()
+        );
+        print.call((line,), Some(&kwargs))?;
+    }
+
+    Ok(())
+}
+
+/// Describe every member as a JSON array of objects.
+pub(crate) fn metadata_json(py: Python<'_>, members: &[impl MemberMetadata]) -> PyResult<String> {
+    let objects = PyList::empty(py);
+    for member in members {
+        let object = PyDict::new(py);
+        object.set_item(intern!(py, "filename"), member.name())?;
+        object.set_item(intern!(py, "compress_size"), member.compress_size())?;
+        object.set_item(intern!(py, "file_size"), member.file_size())?;
+        object.set_item(intern!(py, "CRC"), member.crc())?;
+        object.set_item(intern!(py, "compress_type"), member.compress_type())?;
+        object.set_item(intern!(py, "date_time"), member.date_time())?;
+        objects.append(object)?;
+    }
+
+    py.import(intern!(py, "json"))?
+        .call_method1(intern!(py, "dumps"), (objects,))?
+        .extract()
+}
+
+/// Count the members using each compression method id.
+pub(crate) fn compress_type_counts(members: &[impl MemberMetadata]) -> BTreeMap<u16, usize> {
+    let mut counts: BTreeMap<u16, usize> = BTreeMap::new();
+    for member in members {
+        *counts.entry(member.compress_type()).or_default() += 1;
+    }
+
+    counts
+}
+
+/// Get the most common compression method id, or `None` if there are no members.
+///
+/// Ties are broken in favor of the lowest id.
+pub(crate) fn dominant_compress_type(members: &[impl MemberMetadata]) -> Option<u16> {
+    compress_type_counts(members)
+        .into_iter()
+        .rev()
+        .max_by_key(|(_compress_type, count)| *count)
+        .map(|(compress_type, _count)| compress_type)
+}
+
+/// Get the extension of a member name, including the dot, like `os.path.splitext`.
+///
+/// Names without an extension, or whose only dot is leading, have an empty extension.
+fn extension(name: &str) -> &str {
+    let file_name = name.rsplit('/').next().unwrap_or(name);
+    let stem_start = file_name.len() - file_name.trim_start_matches('.').len();
+
+    match file_name[stem_start..].rfind('.') {
+        Some(index) => &file_name[stem_start + index..],
+        None => "",
+    }
+}
@@ -0,0 +1,92 @@
+use crate::DateTimeTuple;
+use pyo3::exceptions::PyOSError;
+use pyo3::exceptions::PyOverflowError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use std::time::Duration;
+use std::time::SystemTime;
+use zip::DateTime;
+
+// Zip timestamps have no time zone, and are local time by convention.
+// Like `zipfile`, conversions go through Python's `time.mktime` and `time.localtime`,
+// so that they agree with it and with other tools on the same machine,
+// and follow changes to `TZ` made with `time.tzset`.
+
+/// Convert a zip timestamp tuple into a Unix timestamp, taking it as local time.
+///
+/// Invalid timestamps, like ones with a zero month, give `None`.
+pub(crate) fn unix_timestamp(py: Python<'_>, date_time: DateTimeTuple) -> PyResult<Option<i64>> {
+    let (year, month, day, hour, minute, second) = date_time;
+    let Ok(month) = time::Month::try_from(month) else {
+        return Ok(None);
+    };
+    if time::Date::from_calendar_date(year.into(), month, day).is_err()
+        || time::Time::from_hms(hour, minute, second).is_err()
+    {
+        return Ok(None);
+    }
+
+    // The weekday and day of the year are ignored, and -1 lets `mktime` work out daylight saving time.
+    let fields = (year, u8::from(month), day, hour, minute, second, 0, 0, -1);
+    let timestamp: f64 = py
+        .import(intern!(py, "time"))?
+        .call_method1(intern!(py, "mktime"), (fields,))?
+        .extract()?;
+
+    Ok(Some(timestamp as i64))
+}
+
+/// Convert a zip timestamp tuple into a `SystemTime`, taking it as local time.
+///
+/// Invalid timestamps give `None`, like with `unix_timestamp`.
+pub(crate) fn system_time(
+    py: Python<'_>,
+    date_time: DateTimeTuple,
+) -> PyResult<Option<SystemTime>> {
+    Ok(unix_timestamp(py, date_time)?.map(|timestamp| {
+        let offset = Duration::from_secs(timestamp.unsigned_abs());
+        if timestamp < 0 {
+            SystemTime::UNIX_EPOCH - offset
+        } else {
+            SystemTime::UNIX_EPOCH + offset
+        }
+    }))
+}
+
+/// Convert a `SystemTime` into a zip timestamp in local time.
+///
+/// Times that a zip timestamp cannot hold, like ones before 1980, give `None`.
+pub(crate) fn local_date_time(py: Python<'_>, time: SystemTime) -> PyResult<Option<DateTime>> {
+    let timestamp = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(duration) => duration.as_secs() as i64,
+        Err(error) => -(error.duration().as_secs_f64().ceil() as i64),
+    };
+
+    let local = match py
+        .import(intern!(py, "time"))?
+        .call_method1(intern!(py, "localtime"), (timestamp,))
+    {
+        Ok(local) => local,
+        // Platforms differ on which times `localtime` supports.
+        Err(error)
+            if error.is_instance_of::<PyOverflowError>(py)
+                || error.is_instance_of::<PyOSError>(py) =>
+        {
+            return Ok(None);
+        }
+        Err(error) => return Err(error),
+    };
+    let (year, month, day, hour, minute, second, ..): (i64, u8, u8, u8, u8, u8, i64, i64, i64) =
+        local.extract()?;
+
+    let Ok(year) = u16::try_from(year) else {
+        return Ok(None);
+    };
+
+    Ok(DateTime::from_date_and_time(year, month, day, hour, minute, second).ok())
+}
+
+/// Get the current time as a zip timestamp in local time, for members written without one.
+pub(crate) fn now(py: Python<'_>) -> PyResult<DateTime> {
+    Ok(local_date_time(py, SystemTime::now())?.unwrap_or_default())
+}
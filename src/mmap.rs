@@ -0,0 +1,90 @@
+use std::fs::File;
+use std::ptr::NonNull;
+
+/// A read-only memory mapping of a whole file.
+///
+/// Like any file mapping, the contents may change under us if another process modifies the file,
+/// and truncating the file while it is mapped may crash the process.
+#[derive(Debug)]
+pub(crate) struct Mmap {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+
+// SAFETY: The mapping is read-only and owned by this value, so it can be used from any thread.
+unsafe impl Send for Mmap {}
+// SAFETY: The mapping is never written to, so shared access is fine.
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// Map a whole file into memory.
+    ///
+    /// This fails for empty files and anything but regular files, and on platforms without support.
+    #[cfg(unix)]
+    pub(crate) fn map(file: &File) -> std::io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let metadata = file.metadata()?;
+        if !metadata.is_file() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "only regular files can be mapped",
+            ));
+        }
+        // Slices can be at most `isize::MAX` bytes long.
+        let len = isize::try_from(metadata.len())
+            .and_then(usize::try_from)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::Unsupported, error))?;
+        if len == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "cannot map an empty file",
+            ));
+        }
+
+        // SAFETY: The arguments describe a private, read-only mapping of the whole file,
+        // which stays valid after the file is closed.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ptr = NonNull::new(ptr.cast::<u8>())
+            .ok_or_else(|| std::io::Error::other("mmap returned a null pointer"))?;
+
+        Ok(Self { ptr, len })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn map(_file: &File) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "memory mapping is only supported on Unix",
+        ))
+    }
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        // SAFETY: The mapping is `len` bytes long, and lives as long as `self`.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        // SAFETY: The mapping was made by `map`, and is not used after this.
+        unsafe {
+            libc::munmap(self.ptr.as_ptr().cast(), self.len);
+        }
+    }
+}
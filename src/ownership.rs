@@ -0,0 +1,125 @@
+use std::path::Path;
+
+/// The header ID of the Info-ZIP "new Unix" extra field, which holds a UID and GID.
+const INFO_ZIP_UNIX_ID: u16 = 0x7875;
+
+/// Get the UID and GID from the Info-ZIP Unix extra field of a member, if it has one.
+///
+/// The field is a version byte, followed by the UID and the GID,
+/// each as a little-endian number prefixed by its size in bytes.
+/// IDs that don't fit a `u32` are ignored, like malformed fields.
+pub(crate) fn unix_owner(extra_data: &[u8]) -> Option<(u32, u32)> {
+    let mut extra_data = extra_data;
+    while let [id_low, id_high, len_low, len_high, rest @ ..] = extra_data {
+        let id = u16::from_le_bytes([*id_low, *id_high]);
+        let len = usize::from(u16::from_le_bytes([*len_low, *len_high]));
+        let field = rest.get(..len)?;
+        if id == INFO_ZIP_UNIX_ID {
+            return parse_unix_field(field);
+        }
+
+        extra_data = &rest[len..];
+    }
+
+    None
+}
+
+fn parse_unix_field(field: &[u8]) -> Option<(u32, u32)> {
+    let [1, rest @ ..] = field else {
+        return None;
+    };
+    let (uid, rest) = parse_id(rest)?;
+    let (gid, _rest) = parse_id(rest)?;
+
+    Some((uid, gid))
+}
+
+/// Parse a size-prefixed little-endian ID, returning it and the rest of the field.
+fn parse_id(field: &[u8]) -> Option<(u32, &[u8])> {
+    let (&size, rest) = field.split_first()?;
+    let size = usize::from(size);
+    let (bytes, rest) = (rest.get(..size)?, &rest[size..]);
+
+    let mut id: u64 = 0;
+    for (index, &byte) in bytes.iter().enumerate() {
+        if byte != 0 {
+            id |= u64::from(byte).checked_shl(8 * u32::try_from(index).ok()?)?;
+        }
+    }
+
+    Some((u32::try_from(id).ok()?, rest))
+}
+
+/// Change the owner of an extracted file, if we are running as root.
+///
+/// Other users can't give files away, so this silently does nothing for them.
+#[cfg(unix)]
+pub(crate) fn apply_owner(path: &Path, (uid, gid): (u32, u32)) -> std::io::Result<()> {
+    // SAFETY: geteuid has no preconditions, and can't fail.
+    if unsafe { libc::geteuid() } != 0 {
+        return Ok(());
+    }
+
+    std::os::unix::fs::lchown(path, Some(uid), Some(gid))
+}
+
+#[cfg(not(unix))]
+pub(crate) fn apply_owner(_path: &Path, _owner: (u32, u32)) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_info_zip_unix_field() {
+        // An extended timestamp field, then a Unix field with a 4 byte UID and a 2 byte GID.
+        let extra_data = [
+            0x55, 0x54, 0x05, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, // UT
+            0x75, 0x78, 0x09, 0x00, 0x01, 0x04, 0xe8, 0x03, 0x00, 0x00, 0x02, 0x64,
+            0x00, // ux
+        ];
+
+        assert_eq!(unix_owner(&extra_data), Some((1000, 100)));
+    }
+
+    #[test]
+    fn parses_wide_ids_that_fit() {
+        let extra_data = [
+            0x75, 0x78, 0x13, 0x00, 0x01, // ux, version
+            0x08, 0x39, 0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 8 byte UID of 12345
+            0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 8 byte GID of 0
+        ];
+
+        assert_eq!(unix_owner(&extra_data), Some((12345, 0)));
+    }
+
+    #[test]
+    fn skips_missing_and_malformed_fields() {
+        assert_eq!(unix_owner(&[]), None);
+        // Only an extended timestamp field.
+        assert_eq!(
+            unix_owner(&[0x55, 0x54, 0x05, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00]),
+            None
+        );
+        // A Unix field that is shorter than its IDs claim.
+        assert_eq!(
+            unix_owner(&[0x75, 0x78, 0x04, 0x00, 0x01, 0x04, 0xe8, 0x03]),
+            None
+        );
+        // An unknown version.
+        assert_eq!(
+            unix_owner(&[0x75, 0x78, 0x05, 0x00, 0x02, 0x01, 0x01, 0x01, 0x01]),
+            None
+        );
+        // An ID that doesn't fit a u32.
+        assert_eq!(
+            unix_owner(&[
+                0x75, 0x78, 0x0c, 0x00, 0x01, 0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0x01, 0x00,
+            ]),
+            None
+        );
+    }
+}
@@ -1,136 +1,1952 @@
+use crate::central_directory::CentralDirectory;
+use crate::compare::diff_dir;
+use crate::compare::DirDiff;
+use crate::date_time_tuple;
+use crate::encoding::detect_encoding;
+use crate::file::zip_error_to_py;
+use crate::file::ArchiveFile;
+use crate::listing;
+use crate::local_time;
+use crate::missing_member;
+use crate::ownership::apply_owner;
+use crate::ownership::unix_owner;
+use crate::tar::TarCompression;
+use crate::tar::TarWriter;
 use crate::BadZipFile;
+use crate::CompressionKind;
+use crate::ZipBombError;
+use crate::ZipInfo;
+use bzip2::read::BzDecoder;
+use deflate64::Deflate64Decoder;
+use flate2::read::DeflateDecoder;
+use flate2::Crc;
 use parking_lot::ArcMutexGuard;
 use parking_lot::Mutex;
+use pyo3::exceptions::PyIsADirectoryError;
+use pyo3::exceptions::PyNotADirectoryError;
+use pyo3::exceptions::PyNotImplementedError;
 use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::PyTypeError;
 use pyo3::exceptions::PyValueError;
+use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::types::PyByteArray;
 use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
+use pyo3::types::PySlice;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::SystemTime;
+use zip::write::ZipWriter;
 use zip::ZipArchive;
 
 #[derive(Debug)]
 pub(crate) struct ReadZipFile {
-    file: Arc<Mutex<Option<ZipArchive<File>>>>,
+    file: Arc<Mutex<Option<ZipArchive<ArchiveFile>>>>,
+    path: Option<PathBuf>,
+    central_directory: OnceLock<CentralDirectory>,
+    end_of_central_directory: EndOfCentralDirectory,
+    /// The password used for encrypted members when none is given.
+    default_password: Option<Vec<u8>>,
 }
 
 impl ReadZipFile {
-    pub(crate) fn new(file: File) -> PyResult<Self> {
-        let file = ZipArchive::new(file).map_err(|error| BadZipFile::new_err(error.to_string()))?;
+    /// Open an archive for reading.
+    ///
+    /// Data appended after the end of central directory record, like from concatenated files,
+    /// is tolerated, as the zip crate searches backwards for the record.
+    pub(crate) fn new(mut file: ArchiveFile, path: Option<PathBuf>) -> PyResult<Self> {
+        // The zip crate does not expose whether it found Zip64 records, or the disk numbers,
+        // so we look for them ourselves.
+        let end_of_central_directory = EndOfCentralDirectory::find(&mut file)?;
+
+        let file =
+            ZipArchive::new(file).map_err(|error| zip_error_to_py(error, BadZipFile::new_err))?;
         Ok(Self {
             file: Arc::new(Mutex::new(Some(file))),
+            path,
+            central_directory: OnceLock::new(),
+            end_of_central_directory,
+            default_password: None,
+        })
+    }
+
+    /// Set the password used for encrypted members when none is given, or clear it with `None`.
+    pub(crate) fn set_default_password(&mut self, pwd: Option<&[u8]>) {
+        self.default_password = pwd.map(<[u8]>::to_vec);
+    }
+
+    /// Close the archive file.
+    pub(crate) fn close(&mut self) -> PyResult<()> {
+        let mut file = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot close file while a file handle is still open")
+        })?;
+
+        if let Some(file) = file.take() {
+            // The zip crate does not expose a way to access the internal file.
+            // This is the best we can do here.
+            drop(file);
+        }
+        self.central_directory.take();
+
+        Ok(())
+    }
+
+    /// Get the cached central directory, reading it if needed.
+    ///
+    /// Once read, this does not need to lock the archive.
+    fn central_directory(&self) -> PyResult<&CentralDirectory> {
+        if let Some(central_directory) = self.central_directory.get() {
+            return Ok(central_directory);
+        }
+
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot read the central directory while a file handle is still open",
+            )
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let central_directory = CentralDirectory::new(lock)?;
+
+        Ok(self.central_directory.get_or_init(|| central_directory))
+    }
+
+    /// Read and cache the central directory now, instead of on first use.
+    pub(crate) fn prefetch(&self) -> PyResult<()> {
+        self.central_directory()?;
+
+        Ok(())
+    }
+
+    /// Find the member name that matches the given name, ignoring case.
+    ///
+    /// An exact match is always preferred.
+    /// If no member matches, the name is returned unchanged.
+    pub(crate) fn resolve_name_ignore_case(&self, name: &str) -> PyResult<String> {
+        let central_directory = self.central_directory()?;
+
+        let name_lowercase = name.to_lowercase();
+        let mut matches = Vec::new();
+        for entry in central_directory.entries() {
+            if entry.name == name {
+                return Ok(entry.name.clone());
+            }
+
+            if entry.name.to_lowercase() == name_lowercase {
+                matches.push(entry.name.as_str());
+            }
+        }
+
+        match matches.as_slice() {
+            [] => Ok(name.into()),
+            [entry_name] => Ok((*entry_name).into()),
+            _ => Err(PyValueError::new_err(format!(
+                "File {name} ambiguously matches multiple members when ignoring case: {}",
+                matches.join(", ")
+            ))),
+        }
+    }
+
+    pub fn open(&self, name: &str, pwd: Option<Bound<'_, PyBytes>>) -> PyResult<ReadZipExtFile> {
+        self.open_with_options(name, pwd, MemberOptions::default())
+    }
+
+    /// Open a member with extra options for how it is read.
+    pub(crate) fn open_with_options(
+        &self,
+        name: &str,
+        pwd: Option<Bound<'_, PyBytes>>,
+        options: MemberOptions,
+    ) -> PyResult<ReadZipExtFile> {
+        // The member is looked up in the central directory before locking,
+        // which caches it so that metadata methods work while the member is open.
+        let index = self
+            .central_directory()?
+            .index_for_name(name)
+            .ok_or_else(|| missing_member(name))?;
+
+        let lock = self.file.try_lock_arc().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot open another file handle while another file handle is still open",
+            )
+        })?;
+
+        let member = PendingMember {
+            archive: self.file.clone(),
+            index,
+            name: name.into(),
+            password: pwd
+                .map(|pwd| pwd.as_bytes().to_vec())
+                .or_else(|| self.default_password.clone()),
+            options,
+        };
+
+        Ok(ReadZipExtFile {
+            name: name.into(),
+            inner: Some(member.open_locked(lock)?),
+            member: Some(member),
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Open a member without locking the archive until the member is first read.
+    ///
+    /// The member is looked up immediately,
+    /// so missing members are still reported by this call.
+    pub(crate) fn open_lazy(
+        &self,
+        name: &str,
+        pwd: Option<Bound<'_, PyBytes>>,
+        options: MemberOptions,
+    ) -> PyResult<ReadZipExtFile> {
+        let index = self
+            .central_directory()?
+            .index_for_name(name)
+            .ok_or_else(|| missing_member(name))?;
+
+        Ok(ReadZipExtFile {
+            name: name.into(),
+            inner: None,
+            member: Some(PendingMember {
+                archive: self.file.clone(),
+                index,
+                name: name.into(),
+                password: pwd
+                    .map(|pwd| pwd.as_bytes().to_vec())
+                    .or_else(|| self.default_password.clone()),
+                options,
+            }),
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Read the whole contents of a member into an existing buffer, replacing its contents.
+    ///
+    /// Returns the number of bytes read.
+    pub(crate) fn read_into_buf(
+        &self,
+        name: &str,
+        pwd: Option<Bound<'_, PyBytes>>,
+        buffer: &Bound<'_, PyByteArray>,
+    ) -> PyResult<usize> {
+        let mut file = self.open(name, pwd)?;
+        let len = file.read_into_buf(buffer);
+        file.close();
+
+        len
+    }
+
+    /// Stream a member through Python hash objects, like ones from `hashlib`.
+    ///
+    /// Each chunk of the member is passed to the `update` method of every hasher.
+    /// If `data` is given, the contents of the member are also appended to it.
+    pub(crate) fn hash<'py>(
+        &self,
+        name: &str,
+        pwd: Option<Bound<'py, PyBytes>>,
+        hashers: &[Bound<'py, PyAny>],
+        mut data: Option<&mut Vec<u8>>,
+        py: Python<'py>,
+    ) -> PyResult<()> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let mut file = self.open(name, pwd)?;
+        let result = loop {
+            let chunk = match file.read_chunk(CHUNK_SIZE) {
+                Ok(chunk) => chunk,
+                Err(error) => break Err(error),
+            };
+            if chunk.is_empty() {
+                break Ok(());
+            }
+
+            let bytes = PyBytes::new(py, &chunk);
+            if let Err(error) = hashers.iter().try_for_each(|hasher| {
+                hasher
+                    .call_method1(intern!(py, "update"), (&bytes,))
+                    .map(drop)
+            }) {
+                break Err(error);
+            }
+            if let Some(data) = data.as_deref_mut() {
+                data.extend_from_slice(&chunk);
+            }
+        };
+        file.close();
+
+        result
+    }
+
+    /// Read `length` bytes of a member, starting at `start` bytes into its decompressed contents.
+    ///
+    /// Stored members without encryption are seeked into directly when no password is in effect.
+    /// Other members are decompressed from the start, discarding everything before `start`.
+    pub(crate) fn read_range(
+        &self,
+        name: &str,
+        start: u64,
+        length: u64,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<Vec<u8>> {
+        // Like `open`, the default password stands in for a missing one.
+        if pwd.is_none() && self.default_password.is_none() {
+            if let Some(data) = self.read_range_stored(name, start, length)? {
+                return Ok(data);
+            }
+        }
+
+        let mut file = self.open(name, pwd)?;
+        let data = file.read_range(start, length);
+        file.close();
+
+        data
+    }
+
+    /// Read a range of a member by seeking, returning `None` if the member isn't stored without encryption.
+    fn read_range_stored(&self, name: &str, start: u64, length: u64) -> PyResult<Option<Vec<u8>>> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot open another file handle while another file handle is still open",
+            )
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let index = lock
+            .index_for_name(name)
+            .ok_or_else(|| missing_member(name))?;
+        let file = lock
+            .by_index_raw(index)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        if file.compression() != zip::CompressionMethod::Stored || file.encrypted() {
+            return Ok(None);
+        }
+        drop(file);
+
+        let mut file = lock
+            .by_index_seek(index)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut buffer = Vec::new();
+        file.take(length).read_to_end(&mut buffer)?;
+
+        Ok(Some(buffer))
+    }
+
+    /// Iterate over the raw compressed data of every member, in central directory order.
+    ///
+    /// The archive stays locked until the iterator is exhausted or closed.
+    pub(crate) fn iter_raw(&self) -> PyResult<RawEntries> {
+        let lock = self.file.try_lock_arc().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot open another file handle while another file handle is still open",
+            )
+        })?;
+
+        if lock.is_none() {
+            return Err(PyValueError::new_err(
+                "Attempt to use ZIP archive that was already closed",
+            ));
+        }
+
+        Ok(RawEntries {
+            lock: Some(lock),
+            index: 0,
+        })
+    }
+
+    /// Read the whole contents of a member.
+    ///
+    /// The member is closed before returning, so the archive lock is released.
+    pub(crate) fn read(&self, name: &str, pwd: Option<Bound<'_, PyBytes>>) -> PyResult<Vec<u8>> {
+        let mut file = self.open(name, pwd)?;
+        let data = file.read();
+        file.close();
+
+        data
+    }
+
+    /// Read the whole contents of a member, retrying as stored if it fails to decompress.
+    ///
+    /// If the retry fails too, the original error is returned.
+    pub(crate) fn read_salvage(
+        &self,
+        name: &str,
+        pwd: Option<Bound<'_, PyBytes>>,
+    ) -> PyResult<Vec<u8>> {
+        let error = match self.read(name, pwd.clone()) {
+            Ok(data) => return Ok(data),
+            Err(error) => error,
+        };
+
+        let options = MemberOptions {
+            force_method: Some(CompressionKind::Stored),
+            ..MemberOptions::default()
+        };
+        let Ok(mut file) = self.open_with_options(name, pwd, options) else {
+            return Err(error);
+        };
+        let data = file.read();
+        file.close();
+
+        data.map_err(|_salvage_error| error)
+    }
+
+    /// Get the number of members, counting duplicate names once.
+    pub(crate) fn len(&self) -> PyResult<usize> {
+        Ok(self.central_directory()?.entries().len())
+    }
+
+    /// Get the name of the member at the given central directory index, if there is one.
+    pub(crate) fn name_at(&self, index: usize) -> PyResult<Option<String>> {
+        let entries = self.central_directory()?.entries();
+
+        Ok(entries.get(index).map(|entry| entry.name.clone()))
+    }
+
+    /// Raise `BadZipFile` if the recorded uncompressed size of a member is not `expected_size`.
+    ///
+    /// This only reads the central directory, so it can be checked before opening the member.
+    /// Missing members are left for opening to report.
+    pub(crate) fn check_expected_size(&self, name: &str, expected_size: u64) -> PyResult<()> {
+        let entry = self
+            .central_directory()?
+            .entries()
+            .iter()
+            .find(|entry| entry.name == name);
+
+        if let Some(entry) = entry {
+            let size = entry.file_size;
+            if size != expected_size {
+                return Err(BadZipFile::new_err(format!(
+                    "File {name} has a size of {size} bytes, expected {expected_size} bytes"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check if the archive has a member with the given name.
+    ///
+    /// This is answered from the central directory, so it works while a member is open.
+    pub(crate) fn contains(&self, name: &str) -> PyResult<bool> {
+        Ok(self.central_directory()?.index_for_name(name).is_some())
+    }
+
+    pub(crate) fn namelist(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        // Names are collected without the GIL,
+        // as this may take a while for archives with huge central directories.
+        // They are only converted into Python strings after the GIL is re-acquired.
+        py.allow_threads(|| {
+            let names = self
+                .central_directory()?
+                .entries()
+                .iter()
+                .map(|entry| entry.name.clone())
+                .collect();
+
+            Ok(names)
+        })
+    }
+
+    /// Get the metadata of a member, raising `KeyError` if there is no such member.
+    pub(crate) fn getinfo(&self, name: &str) -> PyResult<ZipInfo> {
+        self.central_directory()?
+            .entries()
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(ZipInfo::from_entry)
+            .ok_or_else(|| missing_member(name))
+    }
+
+    pub(crate) fn infolist(&self, py: Python<'_>) -> PyResult<Vec<ZipInfo>> {
+        py.allow_threads(|| {
+            let infos = self
+                .central_directory()?
+                .entries()
+                .iter()
+                .map(ZipInfo::from_entry)
+                .collect();
+
+            Ok(infos)
+        })
+    }
+
+    pub(crate) fn glob(&self, pattern: &str, py: Python<'_>) -> PyResult<Vec<String>> {
+        py.allow_threads(|| Ok(listing::glob(self.central_directory()?.entries(), pattern)))
+    }
+
+    /// Compare the members against the files in a directory, without extracting anything.
+    pub(crate) fn diff_dir(&self, directory: &Path, py: Python<'_>) -> PyResult<DirDiff> {
+        py.allow_threads(|| diff_dir(self.central_directory()?.entries(), directory))
+    }
+
+    /// Get the names of members modified after a Unix timestamp, with member timestamps taken as local time.
+    pub(crate) fn modified_since(&self, timestamp: f64, py: Python<'_>) -> PyResult<Vec<String>> {
+        listing::modified_since(py, self.central_directory()?.entries(), timestamp)
+    }
+
+    /// Get the names of the files and directories at the root of the archive.
+    pub(crate) fn toplevel(&self, py: Python<'_>) -> PyResult<BTreeSet<String>> {
+        py.allow_threads(|| Ok(listing::toplevel(self.central_directory()?.entries())))
+    }
+
+    /// Get the `n` largest members by uncompressed size, largest first.
+    pub(crate) fn largest(&self, n: usize, py: Python<'_>) -> PyResult<Vec<(String, u64)>> {
+        py.allow_threads(|| Ok(listing::largest(self.central_directory()?.entries(), n)))
+    }
+
+    /// Get the total uncompressed size of members, grouped by file extension.
+    pub(crate) fn size_by_extension(&self, py: Python<'_>) -> PyResult<BTreeMap<String, u64>> {
+        py.allow_threads(|| {
+            Ok(listing::size_by_extension(
+                self.central_directory()?.entries(),
+            ))
+        })
+    }
+
+    /// Get the names of members that can't be read, in central directory order.
+    ///
+    /// This only looks at metadata, so nothing is decompressed.
+    pub(crate) fn unsupported_entries(&self, py: Python<'_>) -> PyResult<Vec<String>> {
+        py.allow_threads(|| {
+            Ok(listing::unsupported_entries(
+                self.central_directory()?.entries(),
+            ))
+        })
+    }
+
+    /// Print a table of members, like `zipfile`, to a file or to `sys.stdout` if `None`.
+    pub(crate) fn printdir(&self, file: Option<&Bound<'_, PyAny>>, py: Python<'_>) -> PyResult<()> {
+        listing::printdir(py, self.central_directory()?.entries(), file)
+    }
+
+    /// Describe every member as a JSON array of objects.
+    pub(crate) fn metadata_json(&self, py: Python<'_>) -> PyResult<String> {
+        listing::metadata_json(py, self.central_directory()?.entries())
+    }
+
+    /// Check if the archive uses Zip64 end of central directory records,
+    /// or if any entry uses Zip64 extra fields.
+    pub(crate) fn is_zip64(&self) -> PyResult<bool> {
+        let central_directory = self.central_directory()?;
+
+        Ok(self.end_of_central_directory.zip64
+            || central_directory.entries().iter().any(|entry| entry.zip64))
+    }
+
+    /// Guess the encoding of member names from a sample of their raw bytes.
+    pub(crate) fn detect_encoding(&self, py: Python<'_>) -> PyResult<(String, f64)> {
+        const SAMPLE_SIZE: usize = 1000;
+
+        let entries = self.central_directory()?.entries();
+        let names = entries
+            .iter()
+            .take(SAMPLE_SIZE)
+            .map(|entry| entry.name_raw.as_slice());
+
+        detect_encoding(py, names)
+    }
+
+    /// Fail if any member is encrypted.
+    pub(crate) fn require_plaintext(&self) -> PyResult<()> {
+        let central_directory = self.central_directory()?;
+        match central_directory
+            .entries()
+            .iter()
+            .find(|entry| entry.encrypted)
+        {
+            Some(entry) => Err(PyValueError::new_err(format!(
+                "File {} is encrypted, but plaintext was required",
+                entry.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn end_of_central_directory(&self) -> &EndOfCentralDirectory {
+        &self.end_of_central_directory
+    }
+
+    pub(crate) fn dominant_compression(&self) -> PyResult<Option<u16>> {
+        Ok(listing::dominant_compress_type(
+            self.central_directory()?.entries(),
+        ))
+    }
+
+    pub(crate) fn method_histogram(&self) -> PyResult<BTreeMap<u16, usize>> {
+        Ok(listing::compress_type_counts(
+            self.central_directory()?.entries(),
+        ))
+    }
+
+    /// Extract members into a directory, or every member if `members` is `None`.
+    ///
+    /// Extracted files get the modification time of their member, taken as local time.
+    /// With `skip_unchanged`, files that already exist with the same size
+    /// and a modification time within the 2 second resolution of zip timestamps are not rewritten.
+    /// The GIL is released while extracting, as this may take a while for big archives.
+    pub(crate) fn extractall(
+        &self,
+        path: &Path,
+        members: Option<&[String]>,
+        pwd: Option<&[u8]>,
+        options: ExtractOptions,
+        py: Python<'_>,
+    ) -> PyResult<()> {
+        let pwd = pwd.or(self.default_password.as_deref());
+        py.allow_threads(|| self.extractall_without_gil(path, members, pwd, options))
+    }
+
+    fn extractall_without_gil(
+        &self,
+        path: &Path,
+        members: Option<&[String]>,
+        pwd: Option<&[u8]>,
+        options: ExtractOptions,
+    ) -> PyResult<()> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot extract zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let indices = match members {
+            Some(members) => members
+                .iter()
+                .map(|name| {
+                    lock.index_for_name(name)
+                        .ok_or_else(|| missing_member(name))
+                })
+                .collect::<PyResult<Vec<_>>>()?,
+            None => (0..lock.len()).collect(),
+        };
+
+        if let Some(max_files) = options.max_files {
+            if indices.len() > max_files {
+                return Err(ZipBombError::new_err(format!(
+                    "Extracting {} members would exceed max_files of {max_files}",
+                    indices.len()
+                )));
+            }
+        }
+
+        let mut written = 0;
+        for index in indices {
+            extract_member(lock, index, path, pwd, options, &mut written)?;
+        }
+
+        Ok(())
+    }
+
+    /// Read every member, returning the name of the first one that is corrupt.
+    ///
+    /// A member is corrupt if its CRC doesn't match, or its data or local header is invalid.
+    /// Other errors, like unsupported compression methods, are raised.
+    /// Encrypted members are decrypted with the default password.
+    pub(crate) fn testzip(&self, py: Python<'_>) -> PyResult<Option<String>> {
+        let pwd = self.default_password.as_deref();
+        py.allow_threads(|| {
+            let mut lock = self.file.try_lock().ok_or_else(|| {
+                PyRuntimeError::new_err(
+                    "Cannot open another file handle while another file handle is still open",
+                )
+            })?;
+            let lock = lock.as_mut().ok_or_else(|| {
+                PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+            })?;
+
+            for index in 0..lock.len() {
+                let name = lock.name_for_index(index).unwrap_or_default().to_string();
+                let encrypted = lock
+                    .by_index_raw(index)
+                    .map_err(|error| PyRuntimeError::new_err(error.to_string()))?
+                    .encrypted();
+
+                let file = match (encrypted, pwd) {
+                    (false, _) => lock.by_index(index),
+                    (true, Some(pwd)) => lock.by_index_decrypt(index, pwd),
+                    (true, None) => {
+                        return Err(PyRuntimeError::new_err(format!(
+                            "File {name} is encrypted, password required for testing"
+                        )));
+                    }
+                };
+                let mut file = match file {
+                    Ok(file) => file,
+                    Err(zip::result::ZipError::InvalidArchive(_)) => return Ok(Some(name)),
+                    Err(error) => return Err(zip_error_to_py(error, PyRuntimeError::new_err)),
+                };
+                if std::io::copy(&mut file, &mut std::io::sink()).is_err() {
+                    return Ok(Some(name));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    /// Read every member, returning `(name, stored_crc, computed_crc, ok)` for each.
+    ///
+    /// Members are streamed through a fixed-size buffer, so memory use doesn't grow with their size.
+    /// If a member's data or local header is invalid,
+    /// the computed CRC only covers what was read before the error, and it is not ok.
+    /// Other errors are raised like they are by `testzip`.
+    pub(crate) fn audit(&self, py: Python<'_>) -> PyResult<Vec<(String, u32, u32, bool)>> {
+        let pwd = self.default_password.as_deref();
+        py.allow_threads(|| {
+            let mut lock = self.file.try_lock().ok_or_else(|| {
+                PyRuntimeError::new_err(
+                    "Cannot open another file handle while another file handle is still open",
+                )
+            })?;
+            let lock = lock.as_mut().ok_or_else(|| {
+                PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+            })?;
+
+            let mut buffer = vec![0; 64 * 1024];
+            let mut report = Vec::with_capacity(lock.len());
+            for index in 0..lock.len() {
+                let name = lock.name_for_index(index).unwrap_or_default().to_string();
+                let (encrypted, stored_crc) = {
+                    let file = lock
+                        .by_index_raw(index)
+                        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+
+                    (file.encrypted(), file.crc32())
+                };
+
+                let file = match (encrypted, pwd) {
+                    (false, _) => lock.by_index(index),
+                    (true, Some(pwd)) => lock.by_index_decrypt(index, pwd),
+                    (true, None) => {
+                        return Err(PyRuntimeError::new_err(format!(
+                            "File {name} is encrypted, password required for testing"
+                        )));
+                    }
+                };
+                let mut file = match file {
+                    Ok(file) => file,
+                    Err(zip::result::ZipError::InvalidArchive(_)) => {
+                        report.push((name, stored_crc, 0, false));
+                        continue;
+                    }
+                    Err(error) => return Err(zip_error_to_py(error, PyRuntimeError::new_err)),
+                };
+
+                // The zip crate checks the CRC itself, erroring after the last byte is read,
+                // so a mismatch still leaves the whole member hashed.
+                let mut crc = Crc::new();
+                let mut failed = false;
+                loop {
+                    match file.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(n) => crc.update(&buffer[..n]),
+                        Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                        Err(_error) => {
+                            failed = true;
+                            break;
+                        }
+                    }
+                }
+
+                let computed_crc = crc.sum();
+                let ok = !failed && computed_crc == stored_crc;
+                report.push((name, stored_crc, computed_crc, ok));
+            }
+
+            Ok(report)
+        })
+    }
+
+    /// Extract a single member into a directory, returning the path it was extracted to.
+    pub(crate) fn extract(
+        &self,
+        name: &str,
+        path: &Path,
+        pwd: Option<&[u8]>,
+        options: ExtractOptions,
+    ) -> PyResult<PathBuf> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot extract zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let index = lock
+            .index_for_name(name)
+            .ok_or_else(|| missing_member(name))?;
+
+        let pwd = pwd.or(self.default_password.as_deref());
+        extract_member(lock, index, path, pwd, options, &mut 0)
+    }
+
+    /// Write a copy of the archive where every entry is stored without compression.
+    ///
+    /// Each member is streamed through decompression into the new archive, without buffering it whole.
+    pub(crate) fn explode(&self, output_path: &Path) -> PyResult<()> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot explode zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let mut writer = ZipWriter::new(File::create(output_path)?);
+        for index in 0..lock.len() {
+            let mut file = lock
+                .by_index(index)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            let options = file
+                .options()
+                .compression_method(zip::CompressionMethod::Stored);
+
+            if file.is_dir() {
+                writer
+                    .add_directory(file.name(), options)
+                    .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            } else {
+                writer
+                    .start_file(file.name(), options)
+                    .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+                std::io::copy(&mut file, &mut writer)?;
+            }
+        }
+
+        finish_writer(writer)
+    }
+
+    /// Read every member for which `predicate` returns true, in one pass over the archive.
+    ///
+    /// The predicate is called with the `ZipInfo` of each member, in central directory order.
+    pub(crate) fn read_matching<'py>(
+        &self,
+        predicate: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let py = predicate.py();
+
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot open another file handle while another file handle is still open",
+            )
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let contents = PyDict::new(py);
+        for index in 0..lock.len() {
+            let info = ZipInfo::from_zip_file(
+                &lock
+                    .by_index_raw(index)
+                    .map_err(|error| PyRuntimeError::new_err(error.to_string()))?,
+            );
+            let name = info.filename.clone();
+            if !predicate.call1((info,))?.is_truthy()? {
+                continue;
+            }
+
+            let mut file = lock
+                .by_index(index)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            let mut data = Vec::with_capacity(file.size().try_into().unwrap_or(0));
+            file.read_to_end(&mut data)?;
+            contents.set_item(name, PyBytes::new(py, &data))?;
+        }
+
+        Ok(contents)
+    }
+
+    /// Write a copy of the archive with its entries sorted by name.
+    ///
+    /// Entries are raw-copied, so no recompression takes place.
+    pub(crate) fn sorted_copy(&self, output_path: &Path) -> PyResult<()> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot copy zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let mut indices: Vec<usize> = (0..lock.len()).collect();
+        indices.sort_by_key(|&index| lock.name_for_index(index).unwrap_or_default().to_string());
+
+        let mut writer = ZipWriter::new(File::create(output_path)?);
+        for index in indices {
+            let file = lock
+                .by_index_raw(index)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            writer
+                .raw_copy_file(file)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        }
+
+        finish_writer(writer)
+    }
+
+    /// Convert the archive into a tar archive.
+    ///
+    /// Members are streamed through decompression into the tar archive, without buffering them whole.
+    /// Names, sizes, Unix permissions, and modification times, taken as local time, are kept.
+    pub(crate) fn to_tar(
+        &self,
+        py: Python<'_>,
+        output_path: &Path,
+        compression: TarCompression,
+    ) -> PyResult<()> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot convert zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let mut writer = TarWriter::new(File::create(output_path)?, compression);
+        for index in 0..lock.len() {
+            let mut file = lock
+                .by_index(index)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            let name = file.name().to_string();
+            let mtime = match file.last_modified() {
+                Some(date_time) => {
+                    local_time::unix_timestamp(py, date_time_tuple(Some(date_time)))?.unwrap_or(0)
+                }
+                None => 0,
+            };
+
+            if file.is_dir() {
+                let mode = file.unix_mode().map_or(0o755, |mode| mode & 0o7777);
+                writer.append_dir(&name, mode, mtime)?;
+            } else {
+                let mode = file.unix_mode().map_or(0o644, |mode| mode & 0o7777);
+                let size = file.size();
+                writer.append_file(&name, mode, size, mtime, &mut file)?;
+            }
+        }
+
+        writer.finish()
+    }
+
+    /// Split the archive into multiple volumes, each under the given size.
+    ///
+    /// Entries are raw-copied, so no recompression takes place.
+    /// Entries that are larger than the limit are placed in their own volume.
+    /// Volumes are written next to the archive, named like `archive.001.zip`.
+    pub(crate) fn split(&self, max_volume_size: u64) -> PyResult<Vec<PathBuf>> {
+        let mut lock = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot split zip while a file handle is still open")
+        })?;
+        let lock = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let path = self.path.as_ref().ok_or_else(|| {
+            PyValueError::new_err("splitting requires an archive opened from a path")
+        })?;
+
+        let mut paths = Vec::new();
+        let mut writer: Option<ZipWriter<File>> = None;
+        let mut volume_size = 0;
+        for index in 0..lock.len() {
+            let file = lock
+                .by_index_raw(index)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            let entry_size = entry_record_size(&file, volume_size);
+
+            if writer.is_some() && volume_size + entry_size > max_volume_size {
+                if let Some(writer) = writer.take() {
+                    finish_writer(writer)?;
+                }
+            }
+
+            let writer = match &mut writer {
+                Some(writer) => writer,
+                None => {
+                    let path = volume_path(path, paths.len() + 1);
+                    let file = File::create(&path)?;
+                    paths.push(path);
+                    volume_size = end_of_central_directory_size(max_volume_size);
+
+                    writer.insert(ZipWriter::new(file))
+                }
+            };
+
+            writer
+                .raw_copy_file(file)
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            volume_size += entry_size;
+        }
+
+        if let Some(writer) = writer {
+            finish_writer(writer)?;
+        }
+
+        Ok(paths)
+    }
+}
+
+/// The fields of the end of central directory record that the zip crate does not expose.
+#[derive(Debug, Default)]
+pub(crate) struct EndOfCentralDirectory {
+    /// Whether a Zip64 end of central directory locator precedes the record.
+    pub(crate) zip64: bool,
+    /// The number of the disk holding the record.
+    pub(crate) disk_number: u32,
+    /// The number of the disk where the central directory starts.
+    pub(crate) disk_with_central_directory: u32,
+    /// The total number of disks, which is only recorded in the Zip64 locator.
+    pub(crate) total_disks: Option<u32>,
+}
+
+impl EndOfCentralDirectory {
+    /// Find the end of central directory record of a file, and the Zip64 locator before it.
+    ///
+    /// Like the zip crate, the whole file is searched backwards for the record,
+    /// so that it is found even if a lot of data was appended after it.
+    /// If there is no record, the disk numbers of a single-disk archive are returned.
+    fn find(file: &mut ArchiveFile) -> PyResult<Self> {
+        const END_OF_CENTRAL_DIRECTORY_SIGNATURE: &[u8] = b"PK\x05\x06";
+        const END_OF_CENTRAL_DIRECTORY_SIZE: u64 = 22;
+        const ZIP64_LOCATOR_SIGNATURE: &[u8] = b"PK\x06\x07";
+        const ZIP64_LOCATOR_SIZE: u64 = 20;
+
+        let position = rfind_signature(
+            file,
+            END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+            END_OF_CENTRAL_DIRECTORY_SIZE,
+        )?;
+        let Some(position) = position else {
+            file.seek(SeekFrom::Start(0))?;
+            return Ok(Self::default());
+        };
+
+        let mut record = [0; END_OF_CENTRAL_DIRECTORY_SIZE as usize];
+        file.seek(SeekFrom::Start(position))?;
+        file.read_exact(&mut record)?;
+        let disk_number = u32::from(u16::from_le_bytes([record[4], record[5]]));
+        let disk_with_central_directory = u32::from(u16::from_le_bytes([record[6], record[7]]));
+
+        let mut locator = [0; ZIP64_LOCATOR_SIZE as usize];
+        let locator = match position.checked_sub(ZIP64_LOCATOR_SIZE) {
+            Some(locator_position) => {
+                file.seek(SeekFrom::Start(locator_position))?;
+                file.read_exact(&mut locator)?;
+                Some(locator).filter(|locator| locator.starts_with(ZIP64_LOCATOR_SIGNATURE))
+            }
+            None => None,
+        };
+        file.seek(SeekFrom::Start(0))?;
+
+        Ok(match locator {
+            Some(locator) => Self {
+                zip64: true,
+                disk_number,
+                disk_with_central_directory,
+                total_disks: Some(u32::from_le_bytes([
+                    locator[16],
+                    locator[17],
+                    locator[18],
+                    locator[19],
+                ])),
+            },
+            None => Self {
+                zip64: false,
+                disk_number,
+                disk_with_central_directory,
+                total_disks: None,
+            },
         })
     }
+}
 
-    /// Close the archive file.
-    pub(crate) fn close(&mut self) -> PyResult<()> {
-        let mut file = self.file.try_lock().ok_or_else(|| {
-            PyRuntimeError::new_err("Cannot close file while a file handle is still open")
+/// Find the offset of the last occurrence of a record signature in a file,
+/// with at least `record_size` bytes from there to the end of the file.
+///
+/// The file is read backwards in chunks, so memory use doesn't grow with the file.
+fn rfind_signature(
+    file: &mut ArchiveFile,
+    signature: &[u8],
+    record_size: u64,
+) -> PyResult<Option<u64>> {
+    const CHUNK_SIZE: u64 = 64 * 1024;
+
+    let len = file.seek(SeekFrom::End(0))?;
+    let Some(last_start) = len.checked_sub(record_size) else {
+        return Ok(None);
+    };
+
+    // Each chunk overlaps the next by the signature length,
+    // so that signatures that straddle two chunks are found too.
+    let overlap = signature.len() as u64 - 1;
+    let mut end = last_start + 1;
+    let mut buffer = Vec::new();
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK_SIZE);
+        let read_end = (end + overlap).min(len);
+        buffer.clear();
+        file.seek(SeekFrom::Start(start))?;
+        Read::by_ref(file)
+            .take(read_end - start)
+            .read_to_end(&mut buffer)?;
+
+        let position = buffer
+            .windows(signature.len())
+            .rposition(|window| window == signature)
+            .map(|position| start + position as u64)
+            .filter(|position| *position < end);
+        if position.is_some() {
+            return Ok(position);
+        }
+
+        end = start;
+    }
+
+    Ok(None)
+}
+
+/// Options for extracting members.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct ExtractOptions {
+    /// Leave existing files with the same size and modification time alone.
+    pub(crate) skip_unchanged: bool,
+    /// Stop once more than this many bytes would be written in total.
+    pub(crate) max_total_size: Option<u64>,
+    /// Refuse to extract more than this many members.
+    pub(crate) max_files: Option<usize>,
+    /// Restore the owner stored in the Info-ZIP Unix extra field, when running as root.
+    pub(crate) preserve_ownership: bool,
+}
+
+/// Extract a single member into a directory, returning the path it was extracted to.
+///
+/// `written` is the number of bytes written by previous extractions,
+/// and is increased by the size of this member.
+fn extract_member(
+    archive: &mut ZipArchive<ArchiveFile>,
+    index: usize,
+    path: &Path,
+    pwd: Option<&[u8]>,
+    options: ExtractOptions,
+    written: &mut u64,
+) -> PyResult<PathBuf> {
+    let encrypted = archive
+        .by_index_raw(index)
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?
+        .encrypted();
+    let file = if encrypted {
+        let name = archive.name_for_index(index).unwrap_or_default();
+        let password = pwd.ok_or_else(|| {
+            PyRuntimeError::new_err(format!(
+                "File {name} is encrypted, password required for extraction"
+            ))
         })?;
 
-        if let Some(file) = file.take() {
-            // The zip crate does not expose a way to access the internal file.
-            // This is the best we can do here.
-            drop(file);
+        archive.by_index_decrypt(index, password)
+    } else {
+        archive.by_index(index)
+    };
+    let mut file = file.map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+
+    let target = path.join(sanitize_member_path(file.name()));
+    let owner = if options.preserve_ownership {
+        file.extra_data().and_then(unix_owner)
+    } else {
+        None
+    };
+    if file.is_dir() {
+        create_dirs(file.name(), &target)?;
+        if let Some(owner) = owner {
+            apply_owner(&target, owner)?;
+        }
+        return Ok(target);
+    }
+    if let Some(parent) = target.parent() {
+        create_dirs(file.name(), parent)?;
+    }
+    if target.is_dir() {
+        return Err(PyIsADirectoryError::new_err(format!(
+            "Cannot extract file {} to {}, which is an existing directory",
+            file.name(),
+            target.display()
+        )));
+    }
+
+    let modified = match file.last_modified() {
+        Some(date_time) => {
+            Python::with_gil(|py| local_time::system_time(py, date_time_tuple(Some(date_time))))?
         }
+        None => None,
+    };
+    if options.skip_unchanged && is_unchanged(&target, file.size(), modified) {
+        return Ok(target);
+    }
 
-        Ok(())
+    let mut output = File::create(&target)?;
+    match options.max_total_size {
+        Some(max_total_size) => {
+            // Read one byte past the limit, to tell members that end right at it from larger ones.
+            let remaining = max_total_size.saturating_sub(*written);
+            let copied = std::io::copy(
+                &mut Read::by_ref(&mut file).take(remaining + 1),
+                &mut output,
+            )?;
+            if copied > remaining {
+                // The partially written file is removed, so the limit is never exceeded on disk.
+                drop(output);
+                std::fs::remove_file(&target)?;
+
+                return Err(ZipBombError::new_err(format!(
+                    "Extraction stopped after writing {written} bytes, \
+                     as extracting {} would exceed max_total_size of {max_total_size} bytes",
+                    file.name()
+                )));
+            }
+            *written += copied;
+        }
+        None => {
+            *written += std::io::copy(&mut file, &mut output)?;
+        }
+    }
+    if let Some(modified) = modified {
+        output.set_modified(modified)?;
+    }
+    if let Some(owner) = owner {
+        apply_owner(&target, owner)?;
     }
 
-    pub fn open(&self, name: &str, pwd: Option<Bound<'_, PyBytes>>) -> PyResult<ReadZipExtFile> {
-        let lock = self.file.try_lock_arc().ok_or_else(|| {
+    Ok(target)
+}
+
+/// Create a directory and its parents for extracting a member,
+/// failing clearly if a file is in the way.
+fn create_dirs(name: &str, path: &Path) -> PyResult<()> {
+    for ancestor in path.ancestors() {
+        if ancestor.is_dir() {
+            break;
+        }
+
+        if ancestor.exists() {
+            return Err(PyNotADirectoryError::new_err(format!(
+                "Cannot extract {name}, as {} is an existing file where a directory is needed",
+                ancestor.display()
+            )));
+        }
+    }
+
+    std::fs::create_dir_all(path)?;
+
+    Ok(())
+}
+
+/// Turn a member name into a relative path that cannot escape the extraction directory.
+///
+/// Like Python's `zipfile`, absolute paths are made relative,
+/// and empty, `.`, and `..` components are dropped.
+pub(crate) fn sanitize_member_path(name: &str) -> PathBuf {
+    let separators: &[char] = if cfg!(windows) { &['/', '\\'] } else { &['/'] };
+
+    name.split(separators)
+        .filter(|component| !matches!(*component, "" | "." | ".."))
+        .filter(|component| !(cfg!(windows) && component.ends_with(':')))
+        .collect()
+}
+
+/// Get a writable, flat byte view of a bytes-like object, with `memoryview(buffer).cast("B")`.
+fn writable_view<'py>(buffer: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let py = buffer.py();
+    let view = py
+        .import(intern!(py, "builtins"))?
+        .getattr(intern!(py, "memoryview"))?
+        .call1((buffer,))?
+        .call_method1(intern!(py, "cast"), ("B",))?;
+    if view.getattr(intern!(py, "readonly"))?.is_truthy()? {
+        return Err(PyTypeError::new_err(
+            "readinto() argument must be a writable bytes-like object",
+        ));
+    }
+
+    Ok(view)
+}
+
+/// Check if an extracted file already exists with the given size and modification time.
+fn is_unchanged(path: &Path, size: u64, modified: Option<SystemTime>) -> bool {
+    // Zip timestamps only have a 2 second resolution.
+    const TOLERANCE: Duration = Duration::from_secs(2);
+
+    let (Ok(metadata), Some(modified)) = (std::fs::metadata(path), modified) else {
+        return false;
+    };
+    let Ok(file_modified) = metadata.modified() else {
+        return false;
+    };
+    let difference = file_modified
+        .duration_since(modified)
+        .or_else(|_error| modified.duration_since(file_modified))
+        .unwrap_or(Duration::MAX);
+
+    metadata.is_file() && metadata.len() == size && difference < TOLERANCE
+}
+
+/// Get the path of the volume with the given number.
+fn volume_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let file_name = match path.extension() {
+        Some(extension) => format!("{stem}.{number:03}.{}", extension.to_string_lossy()),
+        None => format!("{stem}.{number:03}"),
+    };
+
+    path.with_file_name(file_name)
+}
+
+/// Get an upper bound on the number of bytes an entry will take up in a volume,
+/// including its local header, data, and central directory header.
+fn entry_record_size(file: &zip::read::ZipFile<'_, ArchiveFile>, offset: u64) -> u64 {
+    const LOCAL_HEADER_SIZE: u64 = 30;
+    const CENTRAL_HEADER_SIZE: u64 = 46;
+    const ZIP64_LOCAL_EXTRA_FIELD_SIZE: u64 = 20;
+    const ZIP64_CENTRAL_EXTRA_FIELD_SIZE: u64 = 28;
+
+    let name_size = file.name_raw().len() as u64;
+    let extra_size = file.extra_data().map_or(0, |extra| extra.len()) as u64;
+    let comment_size = file.comment().len() as u64;
+    let zip64 = file.size() >= u64::from(u32::MAX)
+        || file.compressed_size() >= u64::from(u32::MAX)
+        || offset >= u64::from(u32::MAX);
+    let (local_zip64_size, central_zip64_size) = if zip64 {
+        (ZIP64_LOCAL_EXTRA_FIELD_SIZE, ZIP64_CENTRAL_EXTRA_FIELD_SIZE)
+    } else {
+        (0, 0)
+    };
+
+    LOCAL_HEADER_SIZE
+        + name_size
+        + extra_size
+        + local_zip64_size
+        + file.compressed_size()
+        + CENTRAL_HEADER_SIZE
+        + name_size
+        + extra_size
+        + central_zip64_size
+        + comment_size
+}
+
+/// Get an upper bound on the size of the end of central directory records for a volume.
+fn end_of_central_directory_size(max_volume_size: u64) -> u64 {
+    const END_OF_CENTRAL_DIRECTORY_SIZE: u64 = 22;
+    const ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE: u64 = 56 + 20;
+
+    if max_volume_size >= u64::from(u32::MAX) {
+        END_OF_CENTRAL_DIRECTORY_SIZE + ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE
+    } else {
+        END_OF_CENTRAL_DIRECTORY_SIZE
+    }
+}
+
+/// Finish writing an archive, flushing it to disk.
+fn finish_writer(writer: ZipWriter<File>) -> PyResult<()> {
+    let mut file = writer
+        .finish()
+        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+    file.flush()?;
+
+    Ok(())
+}
+
+type ArchiveGuard = ArcMutexGuard<parking_lot::RawMutex, Option<ZipArchive<ArchiveFile>>>;
+
+/// Options for how a member is read.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct MemberOptions {
+    /// Decompress the member with this method, instead of the one stored in its header.
+    ///
+    /// A member opened with a forced method does not have its CRC checked,
+    /// as a mislabeled member is likely to have other broken metadata too.
+    pub(crate) force_method: Option<CompressionKind>,
+    /// Abort with a `ZipBombError` once the member has decompressed to more than
+    /// this many times its compressed size.
+    pub(crate) max_ratio: Option<f64>,
+}
+
+/// A member that was looked up, but not opened yet.
+struct PendingMember {
+    archive: Arc<Mutex<Option<ZipArchive<ArchiveFile>>>>,
+    index: usize,
+    name: String,
+    password: Option<Vec<u8>>,
+    options: MemberOptions,
+}
+
+impl PendingMember {
+    /// Lock the archive and open the member.
+    fn open(&self) -> PyResult<ReadZipExtFileInner> {
+        let lock = self.archive.try_lock_arc().ok_or_else(|| {
             PyRuntimeError::new_err(
                 "Cannot open another file handle while another file handle is still open",
             )
         })?;
 
-        let inner_result = ReadZipExtFileInnerTryBuilder {
+        self.open_locked(lock)
+    }
+
+    /// Open the member with an already locked archive.
+    fn open_locked(&self, lock: ArchiveGuard) -> PyResult<ReadZipExtFileInner> {
+        let Self {
+            index,
+            name,
+            password,
+            options,
+            ..
+        } = self;
+        let index = *index;
+
+        let inner = ReadZipExtFileInnerTryBuilder {
             lock,
             file_builder: |lock| {
                 let lock = lock.as_mut().ok_or_else(|| {
                     PyValueError::new_err("Attempt to use ZIP archive that was already closed")
                 })?;
-
-                let index = lock.index_for_name(name).ok_or_else(|| {
-                    PyRuntimeError::new_err(format!("File {name} does not exist"))
-                })?;
+                let member = |reader| Member {
+                    reader,
+                    max_ratio: options.max_ratio,
+                    position: 0,
+                };
 
                 let encrypted = {
                     let file = lock
                         .by_index_raw(index)
-                        .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+                        .map_err(|error| zip_error_to_py(error, PyRuntimeError::new_err))?;
 
                     file.encrypted()
                 };
 
+                if let Some(force_method) = options.force_method {
+                    if encrypted {
+                        return Err(PyNotImplementedError::new_err(
+                            "forcing the compression method of encrypted files is currently unsupported",
+                        ));
+                    }
+
+                    let file = lock
+                        .by_index_raw(index)
+                        .map_err(|error| zip_error_to_py(error, PyRuntimeError::new_err))?;
+                    let info = ZipInfo::from_zip_file(&file);
+                    let size = file.size();
+                    let compressed_size = file.compressed_size();
+                    let reader: Box<dyn Read + Send + Sync + '_> = match force_method {
+                        CompressionKind::Stored => Box::new(file),
+                        CompressionKind::Deflated => Box::new(DeflateDecoder::new(file)),
+                        CompressionKind::Deflate64 => Box::new(Deflate64Decoder::new(file)),
+                        CompressionKind::Bzip2 => Box::new(BzDecoder::new(file)),
+                        CompressionKind::Lzma => {
+                            return Err(PyNotImplementedError::new_err(
+                                "forcing ZIP_LZMA is currently unsupported",
+                            ));
+                        }
+                    };
+
+                    return Ok(member(MemberReader::Forced {
+                        info,
+                        size,
+                        compressed_size,
+                        reader,
+                    }));
+                }
+
                 if encrypted {
-                    let password = pwd
-                        .as_ref()
-                        .ok_or_else(|| {
-                            PyRuntimeError::new_err(format!(
-                                "File {name} is encrypted, password required for extraction"
-                            ))
-                        })?
-                        .as_bytes();
+                    let password = password.as_deref().ok_or_else(|| {
+                        PyRuntimeError::new_err(format!(
+                            "File {name} is encrypted, password required for extraction"
+                        ))
+                    })?;
 
                     lock.by_index_decrypt(index, password)
-                        .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+                        .map(|file| member(MemberReader::Zip(file)))
+                        .map_err(|error| zip_error_to_py(error, PyRuntimeError::new_err))
                 } else {
                     lock.by_index(index)
-                        .map_err(|error| PyRuntimeError::new_err(error.to_string()))
+                        .map(|file| member(MemberReader::Zip(file)))
+                        .map_err(|error| zip_error_to_py(error, PyRuntimeError::new_err))
                 }
             },
         }
         .try_build()?;
 
-        Ok(ReadZipExtFile {
-            inner: Some(inner_result),
-        })
-    }
-
-    pub(crate) fn namelist(&self) -> PyResult<Vec<String>> {
-        let mut lock = self.file.try_lock().ok_or_else(|| {
-            PyRuntimeError::new_err("Cannot list zip while a file handle is still open")
-        })?;
-        let lock = lock.as_mut().ok_or_else(|| {
-            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
-        })?;
-
-        let names = lock.file_names().map(|v| v.to_string()).collect();
-
-        Ok(names)
+        Ok(inner)
     }
 }
 
 #[ouroboros::self_referencing]
 struct ReadZipExtFileInner {
-    lock: ArcMutexGuard<parking_lot::RawMutex, Option<ZipArchive<File>>>,
+    lock: ArchiveGuard,
 
     #[borrows(mut lock)]
     #[not_covariant]
-    file: zip::read::ZipFile<'this, File>,
+    file: Member<'this>,
+}
+
+/// An open member, along with the limits it is read under.
+struct Member<'a> {
+    reader: MemberReader<'a>,
+    max_ratio: Option<f64>,
+    /// The number of decompressed bytes read so far.
+    position: u64,
+}
+
+impl Member<'_> {
+    /// Get the uncompressed size recorded for the member.
+    fn size(&self) -> u64 {
+        self.reader.size()
+    }
+
+    /// Get the metadata of the member.
+    fn info(&self) -> ZipInfo {
+        self.reader.info()
+    }
+}
+
+impl Read for Member<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.position += n as u64;
+
+        // Checking against the compressed size of the whole member catches bombs partway through,
+        // as soon as the output grows too large for the input.
+        if let Some(max_ratio) = self.max_ratio {
+            let compressed_size = self.reader.compressed_size();
+            if self.position as f64 > max_ratio * compressed_size as f64 {
+                return Err(ZipBombError::new_err(format!(
+                    "File {} decompressed to more than {max_ratio} times its compressed size of {compressed_size} bytes",
+                    self.reader.name()
+                ))
+                .into());
+            }
+        }
+
+        Ok(n)
+    }
+}
+
+/// The reader of an open member.
+enum MemberReader<'a> {
+    /// A member decompressed according to its stored compression method.
+    Zip(zip::read::ZipFile<'a, ArchiveFile>),
+    /// A member decompressed with a method chosen by the caller.
+    Forced {
+        info: ZipInfo,
+        size: u64,
+        compressed_size: u64,
+        reader: Box<dyn Read + Send + Sync + 'a>,
+    },
+}
+
+impl MemberReader<'_> {
+    /// Get the name of the member.
+    fn name(&self) -> &str {
+        match self {
+            Self::Zip(file) => file.name(),
+            Self::Forced { info, .. } => &info.filename,
+        }
+    }
+
+    /// Get the compressed size recorded for the member.
+    fn compressed_size(&self) -> u64 {
+        match self {
+            Self::Zip(file) => file.compressed_size(),
+            Self::Forced {
+                compressed_size, ..
+            } => *compressed_size,
+        }
+    }
+
+    /// Get the uncompressed size recorded for the member.
+    fn size(&self) -> u64 {
+        match self {
+            Self::Zip(file) => file.size(),
+            Self::Forced { size, .. } => *size,
+        }
+    }
+
+    /// Get the metadata of the member.
+    fn info(&self) -> ZipInfo {
+        match self {
+            Self::Zip(file) => ZipInfo::from_zip_file(file),
+            Self::Forced { info, .. } => info.clone(),
+        }
+    }
+}
+
+impl Read for MemberReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Zip(file) => file.read(buf),
+            Self::Forced { reader, .. } => reader.read(buf),
+        }
+    }
 }
 
 pub(crate) struct ReadZipExtFile {
+    /// The name of the member.
+    name: String,
+    /// The open member, or `None` if it has not been opened yet.
     inner: Option<ReadZipExtFileInner>,
+    /// The member to open on first use, for lazily opened members,
+    /// and to reopen when seeking backwards, or `None` once closed.
+    member: Option<PendingMember>,
+    /// Decompressed bytes that were read ahead, by peeking or looking for line endings,
+    /// which come before anything read from `inner`.
+    buffer: Vec<u8>,
 }
 
 impl ReadZipExtFile {
-    pub(crate) fn read(&mut self) -> PyResult<Vec<u8>> {
-        let inner = self.inner.as_mut().ok_or_else(|| {
+    /// The number of bytes to read ahead at once, and the most that peeking will read ahead.
+    const BUFFER_SIZE: usize = 8 * 1024;
+
+    /// Get the open member, opening it first if it was opened lazily.
+    fn inner_mut(&mut self) -> PyResult<&mut ReadZipExtFileInner> {
+        let member = self.member.as_ref().ok_or_else(|| {
             PyValueError::new_err("Attempt to use ZipExtFile that was already closed")
         })?;
+
+        // If opening fails, it is retried on next use.
+        match &mut self.inner {
+            Some(inner) => Ok(inner),
+            inner => Ok(inner.insert(member.open()?)),
+        }
+    }
+
+    /// Get the name of the member.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check if the member was closed.
+    pub(crate) fn closed(&self) -> bool {
+        self.member.is_none()
+    }
+
+    /// Get the current position in the decompressed member.
+    pub(crate) fn tell(&mut self) -> PyResult<u64> {
+        if self.inner.is_none() && self.member.is_some() {
+            return Ok(0);
+        }
+
+        let buffered = self.buffer.len() as u64;
+        let inner = self.inner_mut()?;
+
+        Ok(inner.with_file(|file| file.position) - buffered)
+    }
+
+    /// Move to a new position in the decompressed member, returning the new position.
+    ///
+    /// The position is relative to the start for `whence=0`, the current position for `whence=1`,
+    /// and the recorded size of the member for `whence=2`.
+    /// Members can't be seeked directly, so seeking forwards reads and discards bytes,
+    /// and seeking backwards reopens the member and reads from the start again.
+    pub(crate) fn seek(&mut self, offset: i64, whence: u8) -> PyResult<u64> {
+        let position = self.tell()?;
+        let base = match whence {
+            0 => 0,
+            1 => position,
+            2 => self.inner_mut()?.with_file(|file| file.size()),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "invalid whence ({whence}, should be 0, 1 or 2)"
+                )));
+            }
+        };
+        let target = base.checked_add_signed(offset).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "negative seek position {}",
+                i128::from(base) + i128::from(offset)
+            ))
+        })?;
+
+        let skip = match target.checked_sub(position) {
+            Some(skip) => skip,
+            None => {
+                // Release the archive lock before reopening.
+                self.buffer.clear();
+                self.inner = None;
+                target
+            }
+        };
+
+        self.skip(skip)?;
+
+        self.tell()
+    }
+
+    /// Read and discard up to `size` bytes.
+    fn skip(&mut self, size: u64) -> PyResult<()> {
+        let skipped = self.take_buffered(usize::try_from(size).unwrap_or(usize::MAX));
+        let size = size - skipped.len() as u64;
+        let inner = self.inner_mut()?;
+        inner.with_file_mut(|file| {
+            std::io::copy(&mut file.by_ref().take(size), &mut std::io::sink())?;
+            Ok(())
+        })
+    }
+
+    /// Take up to `size` bytes that were read ahead.
+    fn take_buffered(&mut self, size: usize) -> Vec<u8> {
+        let size = size.min(self.buffer.len());
+        let rest = self.buffer.split_off(size);
+
+        std::mem::replace(&mut self.buffer, rest)
+    }
+
+    /// Read another chunk from the member into the read-ahead buffer.
+    ///
+    /// Returns the number of bytes added, which is 0 at EOF.
+    fn fill_buffer(&mut self) -> PyResult<usize> {
+        let inner = self.inner_mut()?;
+        let chunk = inner.with_file_mut(|file| {
+            let mut chunk = Vec::with_capacity(Self::BUFFER_SIZE);
+            file.take(Self::BUFFER_SIZE as u64)
+                .read_to_end(&mut chunk)?;
+            PyResult::Ok(chunk)
+        })?;
+        self.buffer.extend_from_slice(&chunk);
+
+        Ok(chunk.len())
+    }
+
+    /// Read the rest of the member.
+    pub(crate) fn read(&mut self) -> PyResult<Vec<u8>> {
+        let mut buffer = self.take_buffered(usize::MAX);
+        let inner = self.inner_mut()?;
         inner.with_file_mut(|file| {
-            let size = usize::try_from(file.size())
+            let size = usize::try_from(file.size().saturating_sub(file.position))
                 .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
-            let mut buffer = Vec::with_capacity(size);
+            buffer.reserve(size);
             file.read_to_end(&mut buffer)?;
             Ok(buffer)
         })
     }
 
+    /// Read the rest of the member into an existing buffer, replacing its contents.
+    ///
+    /// The buffer is resized to fit, which lets it reuse its allocation across reads.
+    pub(crate) fn read_into_buf(&mut self, buffer: &Bound<'_, PyByteArray>) -> PyResult<usize> {
+        const CHUNK_SIZE: usize = 16 * 1024;
+
+        let buffered = self.take_buffered(usize::MAX);
+        let inner = self.inner_mut()?;
+        inner.with_file_mut(|file| {
+            let size = usize::try_from(file.size().saturating_sub(file.position))
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            buffer.resize(buffered.len() + size)?;
+
+            // SAFETY: No Python code runs while the slice is borrowed,
+            // so the bytearray cannot be resized or freed under us.
+            unsafe {
+                buffer.as_bytes_mut()[..buffered.len()].copy_from_slice(&buffered);
+            }
+
+            let mut chunk = [0; CHUNK_SIZE];
+            let mut len = buffered.len();
+            loop {
+                let n = file.read(&mut chunk)?;
+                if n == 0 {
+                    break;
+                }
+
+                // The recorded size may be wrong.
+                if len + n > buffer.len() {
+                    buffer.resize(len + n)?;
+                }
+
+                // SAFETY: No Python code runs while the slice is borrowed,
+                // so the bytearray cannot be resized or freed under us.
+                unsafe {
+                    buffer.as_bytes_mut()[len..len + n].copy_from_slice(&chunk[..n]);
+                }
+                len += n;
+            }
+
+            if len != buffer.len() {
+                buffer.resize(len)?;
+            }
+
+            Ok(len)
+        })
+    }
+
+    /// Read up to `buffer.len()` bytes into a slice, returning the number of bytes read.
+    ///
+    /// Read-ahead bytes are used first, and fewer bytes may be read even before EOF.
+    fn read_some(&mut self, buffer: &mut [u8]) -> PyResult<usize> {
+        if !self.buffer.is_empty() {
+            let buffered = self.take_buffered(buffer.len());
+            buffer[..buffered.len()].copy_from_slice(&buffered);
+            return Ok(buffered.len());
+        }
+
+        let inner = self.inner_mut()?;
+        inner.with_file_mut(|file| Ok(file.read(buffer)?))
+    }
+
+    /// Read into a writable bytes-like object until it is full or EOF is reached,
+    /// returning the number of bytes read.
+    ///
+    /// `bytearray`s are written to directly.
+    /// Other objects, like `memoryview`s, are written to through slice assignment,
+    /// which copies each chunk through a temporary `bytes`.
+    pub(crate) fn readinto(&mut self, buffer: &Bound<'_, PyAny>) -> PyResult<usize> {
+        const CHUNK_SIZE: usize = 16 * 1024;
+
+        let py = buffer.py();
+        let (bytearray, view) = match buffer.downcast::<PyByteArray>() {
+            Ok(bytearray) => (Some(bytearray), None),
+            Err(_error) => (None, Some(writable_view(buffer)?)),
+        };
+        let len = match (bytearray, &view) {
+            (Some(bytearray), _) => bytearray.len(),
+            (None, Some(view)) => view.len()?,
+            (None, None) => 0,
+        };
+
+        let mut chunk = [0; CHUNK_SIZE];
+        let mut total = 0;
+        while total < len {
+            let n = self.read_some(&mut chunk[..CHUNK_SIZE.min(len - total)])?;
+            if n == 0 {
+                break;
+            }
+
+            if let Some(bytearray) = bytearray {
+                // SAFETY: No Python code runs while the slice is borrowed,
+                // so the bytearray cannot be resized or freed under us.
+                let target = unsafe { bytearray.as_bytes_mut().get_mut(total..total + n) };
+                // Reading from file-like archives runs Python code, which could resize it.
+                let target = target.ok_or_else(|| {
+                    PyRuntimeError::new_err("bytearray was resized during readinto()")
+                })?;
+                target.copy_from_slice(&chunk[..n]);
+            } else if let Some(view) = &view {
+                let slice = PySlice::new(py, total as isize, (total + n) as isize, 1);
+                view.set_item(slice, PyBytes::new(py, &chunk[..n]))?;
+            }
+            total += n;
+        }
+
+        Ok(total)
+    }
+
+    /// Get the metadata of the open member.
+    pub(crate) fn info(&mut self) -> PyResult<ZipInfo> {
+        let inner = self.inner_mut()?;
+
+        Ok(inner.with_file(|file| file.info()))
+    }
+
+    /// Skip `start` bytes, then read up to `length` bytes.
+    pub(crate) fn read_range(&mut self, start: u64, length: u64) -> PyResult<Vec<u8>> {
+        self.skip(start)?;
+
+        self.read_chunk(usize::try_from(length).unwrap_or(usize::MAX))
+    }
+
+    /// Read up to `size` bytes, returning an empty buffer at EOF.
+    ///
+    /// Fewer bytes are only returned if EOF is reached.
+    pub(crate) fn read_chunk(&mut self, size: usize) -> PyResult<Vec<u8>> {
+        let mut buffer = self.take_buffered(size);
+        let size = size - buffer.len();
+        let inner = self.inner_mut()?;
+        inner.with_file_mut(|file| {
+            // Huge sizes are requested to read "everything", so don't allocate more than is left.
+            let remaining = file.size().saturating_sub(file.position);
+            let capacity = usize::try_from(remaining).map_or(size, |remaining| size.min(remaining));
+            buffer.reserve(capacity);
+            file.take(size as u64).read_to_end(&mut buffer)?;
+            Ok(buffer)
+        })
+    }
+
+    /// Read up to and including the next `\n`, or up to `size` bytes if given.
+    ///
+    /// Returns an empty buffer at EOF.
+    pub(crate) fn readline(&mut self, size: Option<usize>) -> PyResult<Vec<u8>> {
+        let size = size.unwrap_or(usize::MAX);
+
+        // Only search bytes that weren't searched before.
+        let mut searched = 0;
+        loop {
+            let end = self.buffer.len().min(size);
+            if let Some(index) = self.buffer[searched..end]
+                .iter()
+                .position(|&byte| byte == b'\n')
+            {
+                return Ok(self.take_buffered(searched + index + 1));
+            }
+            if end == size {
+                return Ok(self.take_buffered(size));
+            }
+            searched = end;
+
+            if self.fill_buffer()? == 0 {
+                return Ok(self.take_buffered(size));
+            }
+        }
+    }
+
+    /// Get upcoming bytes without consuming them.
+    ///
+    /// At least `size` bytes are returned unless EOF is reached first,
+    /// though no more than the buffer size is read ahead for this.
+    /// More bytes may be returned if they were already read ahead.
+    pub(crate) fn peek(&mut self, size: usize) -> PyResult<Vec<u8>> {
+        let size = size.clamp(1, Self::BUFFER_SIZE);
+        while self.buffer.len() < size {
+            if self.fill_buffer()? == 0 {
+                break;
+            }
+        }
+
+        Ok(self.buffer.clone())
+    }
+
+    /// Read lines until EOF, or until the lines read total at least `hint` bytes.
+    ///
+    /// A last line without a trailing `\n` is included.
+    pub(crate) fn readlines(&mut self, hint: Option<usize>) -> PyResult<Vec<Vec<u8>>> {
+        let mut lines = Vec::new();
+        let mut total = 0;
+        loop {
+            let line = self.readline(None)?;
+            if line.is_empty() {
+                return Ok(lines);
+            }
+
+            total += line.len();
+            lines.push(line);
+            if hint.is_some_and(|hint| total >= hint) {
+                return Ok(lines);
+            }
+        }
+    }
+
     pub(crate) fn close(&mut self) {
+        self.member.take();
+        self.buffer = Vec::new();
         if let Some(inner) = self.inner.take() {
             drop(inner);
         }
@@ -140,3 +1956,47 @@ impl ReadZipExtFile {
         self.close();
     }
 }
+
+/// An iterator over the raw compressed data of the members of an archive.
+pub(crate) struct RawEntries {
+    lock: Option<ArchiveGuard>,
+    index: usize,
+}
+
+impl RawEntries {
+    /// Get the next member's metadata and raw data, or `None` once all members were read.
+    ///
+    /// The archive lock is released once the iterator is exhausted.
+    pub(crate) fn next_entry(&mut self) -> PyResult<Option<(ZipInfo, Vec<u8>)>> {
+        let Some(lock) = self.lock.as_mut() else {
+            return Ok(None);
+        };
+        let archive = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        if self.index >= archive.len() {
+            self.close();
+            return Ok(None);
+        }
+
+        let mut file = archive
+            .by_index_raw(self.index)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        self.index += 1;
+
+        let info = ZipInfo::from_zip_file(&file);
+        let size = usize::try_from(file.compressed_size())
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        let mut data = Vec::with_capacity(size);
+        file.read_to_end(&mut data)?;
+
+        Ok(Some((info, data)))
+    }
+
+    pub(crate) fn close(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            drop(lock);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use bzip2::write::BzEncoder;
+use flate2::write::GzEncoder;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Write;
+
+/// The size of a tar block.
+const BLOCK_SIZE: usize = 512;
+
+/// The size of a tar record, which the archive is padded to, like Python's `tarfile`.
+const RECORD_SIZE: u64 = 20 * BLOCK_SIZE as u64;
+
+/// The compression of a tar archive, named like the modes of Python's `tarfile`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub(crate) enum TarCompression {
+    None,
+    Gzip,
+    Bzip2,
+}
+
+impl TryFrom<Option<&str>> for TarCompression {
+    type Error = PyErr;
+
+    fn try_from(value: Option<&str>) -> Result<Self, Self::Error> {
+        match value {
+            None => Ok(Self::None),
+            Some("gz") => Ok(Self::Gzip),
+            Some("bz2") => Ok(Self::Bzip2),
+            Some(value) => Err(PyValueError::new_err(format!(
+                "{value} is not a known tar compression, expected None, \"gz\", or \"bz2\""
+            ))),
+        }
+    }
+}
+
+/// The output of a tar writer, compressed or not.
+enum TarOutput {
+    None(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Bzip2(BzEncoder<BufWriter<File>>),
+}
+
+impl Write for TarOutput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::None(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Bzip2(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::None(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Bzip2(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// A minimal writer for GNU tar archives.
+///
+/// Names longer than the header allows are written with GNU long name entries,
+/// and sizes too large for octal are written in base-256.
+pub(crate) struct TarWriter {
+    output: TarOutput,
+    position: u64,
+}
+
+impl TarWriter {
+    /// Make a writer for a new tar archive.
+    pub(crate) fn new(file: File, compression: TarCompression) -> Self {
+        let file = BufWriter::new(file);
+        let output = match compression {
+            TarCompression::None => TarOutput::None(file),
+            TarCompression::Gzip => TarOutput::Gzip(GzEncoder::new(file, Default::default())),
+            TarCompression::Bzip2 => TarOutput::Bzip2(BzEncoder::new(file, Default::default())),
+        };
+
+        Self {
+            output,
+            position: 0,
+        }
+    }
+
+    /// Add a directory.
+    pub(crate) fn append_dir(&mut self, name: &str, mode: u32, mtime: i64) -> PyResult<()> {
+        self.write_header(name, mode, 0, mtime, b'5')
+    }
+
+    /// Add a file, streaming exactly `size` bytes from `reader`.
+    pub(crate) fn append_file(
+        &mut self,
+        name: &str,
+        mode: u32,
+        size: u64,
+        mtime: i64,
+        reader: &mut impl Read,
+    ) -> PyResult<()> {
+        self.write_header(name, mode, size, mtime, b'0')?;
+
+        let copied = std::io::copy(reader, &mut self.output)?;
+        if copied != size {
+            return Err(PyValueError::new_err(format!(
+                "File {name} has {copied} bytes, but its recorded size is {size} bytes"
+            )));
+        }
+        self.position += copied;
+        self.pad()
+    }
+
+    /// Write the end of archive marker and flush the output.
+    pub(crate) fn finish(mut self) -> PyResult<()> {
+        self.write_all(&[0; 2 * BLOCK_SIZE])?;
+        let padding = self.position.next_multiple_of(RECORD_SIZE) - self.position;
+        self.write_all(&vec![0; padding as usize])?;
+
+        let file = match self.output {
+            TarOutput::None(writer) => writer,
+            TarOutput::Gzip(encoder) => encoder.finish()?,
+            TarOutput::Bzip2(encoder) => encoder.finish()?,
+        };
+        file.into_inner().map_err(|error| error.into_error())?;
+
+        Ok(())
+    }
+
+    fn write_header(
+        &mut self,
+        name: &str,
+        mode: u32,
+        size: u64,
+        mtime: i64,
+        typeflag: u8,
+    ) -> PyResult<()> {
+        let name = name.as_bytes();
+        if name.len() > 100 {
+            // The name is stored as the data of a preceding entry, with a trailing nul.
+            let long_name_size = name.len() as u64 + 1;
+            let header = header(b"././@LongLink", 0, long_name_size, 0, b'L');
+            self.write_all(&header)?;
+            self.write_all(name)?;
+            self.write_all(&[0])?;
+            self.pad()?;
+        }
+
+        let header = header(name, mode, size, mtime.max(0) as u64, typeflag);
+        self.write_all(&header)
+    }
+
+    /// Pad the output with zeros to the next block.
+    fn pad(&mut self) -> PyResult<()> {
+        let block_size = BLOCK_SIZE as u64;
+        let padding = self.position.next_multiple_of(block_size) - self.position;
+        self.write_all(&vec![0; padding as usize])
+    }
+
+    fn write_all(&mut self, buffer: &[u8]) -> PyResult<()> {
+        self.output.write_all(buffer)?;
+        self.position += buffer.len() as u64;
+
+        Ok(())
+    }
+}
+
+/// Build a GNU tar header block.
+///
+/// The name is truncated to fit, so long names need a long name entry before the header.
+fn header(name: &[u8], mode: u32, size: u64, mtime: u64, typeflag: u8) -> [u8; BLOCK_SIZE] {
+    let mut header = [0; BLOCK_SIZE];
+
+    let name_len = name.len().min(100);
+    header[..name_len].copy_from_slice(&name[..name_len]);
+    write_octal(&mut header[100..108], mode.into());
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_number(&mut header[124..136], size);
+    write_number(&mut header[136..148], mtime);
+    header[156] = typeflag;
+    header[257..265].copy_from_slice(b"ustar  \0");
+
+    // The checksum is computed with its own field filled with spaces.
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+    write_octal(&mut header[148..155], checksum.into());
+    header[155] = b' ';
+
+    header
+}
+
+/// Write a nul-terminated, zero-padded octal number that fills the field.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let octal = format!("{value:0digits$o}");
+    field[..digits].copy_from_slice(octal.as_bytes());
+    field[digits] = 0;
+}
+
+/// Write a number in octal if it fits, or in GNU base-256 otherwise.
+fn write_number(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    if value < 1 << (3 * digits) {
+        write_octal(field, value);
+        return;
+    }
+
+    field.fill(0);
+    let bytes = value.to_be_bytes();
+    let start = field.len() - bytes.len();
+    field[start..].copy_from_slice(&bytes);
+    field[0] = 0x80;
+}
@@ -1,93 +1,764 @@
+use super::Compatibility;
 use super::CompressionKind;
+use super::DeflateBackend;
+use crate::date_time_tuple;
+use crate::file::ArchiveFile;
+use crate::local_time;
+use crate::missing_member;
+use crate::BadZipFile;
+use crate::LargeZipFile;
 use crate::ZipInfo;
+use flate2::Crc;
 use parking_lot::ArcMutexGuard;
 use parking_lot::Mutex;
+use pyo3::exceptions::PyIsADirectoryError;
+use pyo3::exceptions::PyNotImplementedError;
 use pyo3::exceptions::PyRuntimeError;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3::types::PyString;
+use std::borrow::Cow;
 use std::fs::File;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use zip::write::SimpleFileOptions;
 use zip::write::ZipWriter;
+use zip::DateTime;
+use zip::ZipArchive;
+
+/// The number of iterations zopfli uses when no compression level is given.
+///
+/// This matches the default of the zopfli crate.
+const ZOPFLI_DEFAULT_ITERATIONS: u8 = 15;
+
+/// How many leading bytes of a member are sampled to decide if it should be stored.
+const AUTO_STORE_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// The entropy, in bits per byte, above which sampled data is considered incompressible.
+///
+/// Random and already-compressed data comes out close to 8, or slightly less for short samples.
+const AUTO_STORE_ENTROPY: f64 = 7.5;
+
+/// The largest member size that is written without Zip64 extensions, matching Python's `zipfile`.
+const ZIP64_LIMIT: u64 = (1 << 31) - 1;
+
+/// The most members an archive can have without Zip64 extensions, matching the zip crate.
+const ZIP64_ENTRY_LIMIT: usize = u16::MAX as usize;
 
 #[derive(Debug)]
 pub struct WriteZipFile {
-    file: Arc<Mutex<Option<ZipWriter<File>>>>,
+    file: Arc<Mutex<Option<ZipWriter<TrackedFile>>>>,
+    /// The position of the writer, shared with the file it writes to.
+    position: Arc<WritePosition>,
     compression_kind: CompressionKind,
     compression_level: Option<u8>,
+    compatibility: Compatibility,
+    deflate_backend: DeflateBackend,
+    max_entry_size: Option<u64>,
+    /// Whether members may use Zip64 extensions, like `allowZip64`.
+    allow_zip64: bool,
+    /// The members in the archive, including any that is still being written.
+    entries: Arc<Mutex<WrittenEntries>>,
 }
 
 impl WriteZipFile {
     pub fn new(
-        file: File,
+        file: ArchiveFile,
         compression_kind: CompressionKind,
         compression_level: Option<u8>,
+        compatibility: Compatibility,
+        deflate_backend: DeflateBackend,
     ) -> PyResult<Self> {
+        let (file, position) = TrackedFile::new(file)?;
         let file = ZipWriter::new(file);
         Ok(Self {
             file: Arc::new(Mutex::new(Some(file))),
+            position,
+            compression_kind,
+            compression_level,
+            compatibility,
+            deflate_backend,
+            max_entry_size: None,
+            allow_zip64: true,
+            entries: Arc::default(),
+        })
+    }
+
+    /// Open an existing archive for appending.
+    ///
+    /// An empty file is treated as a new archive.
+    pub fn new_append(
+        mut file: ArchiveFile,
+        compression_kind: CompressionKind,
+        compression_level: Option<u8>,
+        compatibility: Compatibility,
+        deflate_backend: DeflateBackend,
+    ) -> PyResult<Self> {
+        let is_empty = file.seek(SeekFrom::End(0))? == 0;
+        let entries = if is_empty {
+            Vec::new()
+        } else {
+            existing_entries(&mut file)?
+        };
+
+        let (file, position) = TrackedFile::new(file)?;
+        let file = if is_empty {
+            ZipWriter::new(file)
+        } else {
+            ZipWriter::new_append(file).map_err(|error| BadZipFile::new_err(error.to_string()))?
+        };
+
+        Ok(Self {
+            file: Arc::new(Mutex::new(Some(file))),
+            position,
             compression_kind,
             compression_level,
+            compatibility,
+            deflate_backend,
+            max_entry_size: None,
+            allow_zip64: true,
+            entries: Arc::new(Mutex::new(WrittenEntries::new(entries))),
         })
     }
 
+    /// Limit the uncompressed size of every member written from now on.
+    pub(crate) fn set_max_entry_size(&mut self, max_entry_size: Option<u64>) {
+        self.max_entry_size = max_entry_size;
+    }
+
+    /// Allow or forbid Zip64 extensions for every member written from now on.
+    pub(crate) fn set_allow_zip64(&mut self, allow_zip64: bool) {
+        self.allow_zip64 = allow_zip64;
+    }
+
     /// Close the archive file.
+    ///
+    /// Closing an archive that was already closed does nothing.
     pub(crate) fn close(&mut self) -> PyResult<()> {
-        if let Some(file) = self.file.lock().take() {
+        // Waiting for the lock would deadlock if the open handle belongs to this thread.
+        let mut file = self.file.try_lock().ok_or_else(|| {
+            PyRuntimeError::new_err("Cannot close file while a file handle is still open")
+        })?;
+
+        if let Some(file) = file.take() {
             let mut writer = file
                 .finish()
                 .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            self.entries.lock().finish_last(&self.position);
             writer.flush()?;
         }
 
         Ok(())
     }
 
-    pub fn open(&self, name: &Bound<'_, PyAny>) -> PyResult<WriteZipExtFile> {
-        let mut lock = self.file.try_lock_arc().ok_or_else(|| {
-            PyRuntimeError::new_err(
-                "Cannot open another file handle while another file handle is still open",
-            )
+    /// Start a new member, returning a handle to write its contents.
+    ///
+    /// The size of the member is not known up front,
+    /// so it only uses Zip64 extensions if `force_zip64` is set.
+    pub fn open(&self, name: &Bound<'_, PyAny>, force_zip64: bool) -> PyResult<WriteZipExtFile> {
+        let (lock, name) = self.start_file(
+            name,
+            self.large_file(force_zip64, None)?,
+            false,
+            None,
+            None,
+            None,
+        )?;
+
+        Ok(WriteZipExtFile {
+            name,
+            lock: Some(lock),
+            written: 0,
+            crc: Crc::new(),
+            max_entry_size: self.max_entry_size,
+            entries: self.entries.clone(),
+        })
+    }
+
+    /// Write a new member from an iterable of byte chunks.
+    ///
+    /// The total size is not known up front, so the member only uses Zip64 extensions if `force_zip64` is set.
+    /// If the iterable raises, the partially written member is discarded.
+    /// With `auto_store`, the first chunk is sampled to decide if the member should be stored.
+    pub fn write_iter(
+        &self,
+        name: &Bound<'_, PyAny>,
+        chunks: &Bound<'_, PyAny>,
+        force_zip64: bool,
+        auto_store: bool,
+    ) -> PyResult<()> {
+        let mut chunks = chunks.try_iter()?;
+        let first_chunk = chunks.next().transpose()?;
+        let first_chunk: Option<Cow<'_, [u8]>> = first_chunk
+            .as_ref()
+            .map(|chunk| chunk.extract())
+            .transpose()?;
+
+        let store = auto_store && first_chunk.as_deref().is_some_and(is_incompressible);
+        let (mut lock, _name) = self.start_file(
+            name,
+            self.large_file(force_zip64, None)?,
+            store,
+            None,
+            None,
+            None,
+        )?;
+        let writer = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
         })?;
 
+        let mut written = 0;
+        let mut crc = Crc::new();
+        let result = (|| {
+            if let Some(first_chunk) = first_chunk {
+                written += first_chunk.len() as u64;
+                check_entry_size(self.max_entry_size, written)?;
+                writer.write_all(&first_chunk)?;
+                crc.update(&first_chunk);
+            }
+
+            for chunk in chunks {
+                let chunk = chunk?;
+                let chunk: Cow<'_, [u8]> = chunk.extract()?;
+                written += chunk.len() as u64;
+                check_entry_size(self.max_entry_size, written)?;
+                writer.write_all(&chunk)?;
+                crc.update(&chunk);
+            }
+
+            Ok(())
+        })();
+
+        if let Err(error) = result {
+            writer
+                .abort_file()
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            self.entries.lock().discard_last();
+            return Err(error);
+        }
+        self.entries.lock().update_last(written, crc.sum());
+
+        Ok(())
+    }
+
+    /// Write a member for each item of a mapping of names to contents, in iteration order.
+    ///
+    /// Contents may be bytes-like objects, or strings, which are encoded as UTF-8.
+    /// With `auto_store`, incompressible contents are stored instead of compressed.
+    pub fn write_all(&self, mapping: &Bound<'_, PyDict>, auto_store: bool) -> PyResult<()> {
+        for (name, data) in mapping.iter() {
+            if let Ok(data) = data.downcast::<PyString>() {
+                self.write_bytes(&name, data.to_cow()?.as_bytes(), auto_store, None, None)?;
+            } else {
+                let data: Cow<'_, [u8]> = data.extract()?;
+                self.write_bytes(&name, &data, auto_store, None, None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a member from bytes-like contents, or a string, which is encoded as UTF-8.
+    ///
+    /// `compress_type` and `compress_level` override the compression of the archive,
+    /// or of the `ZipInfo` if one is given.
+    /// `override_crc` is recorded as the CRC of the member instead of the real one.
+    pub fn writestr(
+        &self,
+        name: &Bound<'_, PyAny>,
+        data: &Bound<'_, PyAny>,
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+        override_crc: Option<u32>,
+    ) -> PyResult<()> {
+        let write = |data: &[u8]| match override_crc {
+            Some(crc) => self.write_bytes_with_crc(name, data, compress_type, compress_level, crc),
+            None => self.write_bytes(name, data, false, compress_type, compress_level),
+        };
+
+        if let Ok(data) = data.downcast::<PyString>() {
+            write(data.to_cow()?.as_bytes())
+        } else {
+            let data: Cow<'_, [u8]> = data.extract()?;
+            write(&data)
+        }
+    }
+
+    /// Write a member from a file on the filesystem, named after the file unless `arcname` is given.
+    ///
+    /// The member takes the modification time of the file, in local time, and its Unix permissions.
+    /// The file is streamed into the archive in chunks, without the GIL.
+    pub fn write(
+        &self,
+        py: Python<'_>,
+        path: &Path,
+        arcname: Option<&str>,
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+    ) -> PyResult<()> {
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let mut file = File::open(path)?;
+        let metadata = file.metadata()?;
+        if metadata.is_dir() {
+            return Err(PyIsADirectoryError::new_err(format!(
+                "Cannot write {}, as writing directories is currently unsupported",
+                path.display()
+            )));
+        }
+        check_entry_size(self.max_entry_size, metadata.len())?;
+
+        let arcname = match arcname {
+            Some(arcname) => arcname.to_string(),
+            None => path
+                .file_name()
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!("Cannot get a name for {}", path.display()))
+                })?
+                .to_string_lossy()
+                .into_owned(),
+        };
+        let mut zip_info = ZipInfo::new(&arcname);
+        zip_info.compress_type = u16::from(self.compression_kind);
+        zip_info.compress_level = self.compression_level;
+        let modified = match metadata.modified() {
+            Ok(modified) => local_time::local_date_time(py, modified)?,
+            Err(_error) => None,
+        };
+        zip_info.date_time = date_time_tuple(modified);
+
+        #[cfg(unix)]
+        let permissions = {
+            use std::os::unix::fs::PermissionsExt;
+
+            Some(metadata.permissions().mode())
+        };
+        #[cfg(not(unix))]
+        let permissions = None;
+
+        let large_file = self.large_file(false, Some(metadata.len()))?;
+        let (mut lock, _name) = self.start_file(
+            Bound::new(py, zip_info)?.as_any(),
+            large_file,
+            false,
+            compress_type,
+            compress_level,
+            permissions,
+        )?;
+        let writer = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        let max_entry_size = self.max_entry_size;
+        let result = py.allow_threads(|| {
+            let mut buffer = vec![0; CHUNK_SIZE];
+            let mut written = 0;
+            let mut crc = Crc::new();
+            loop {
+                let n = file.read(&mut buffer)?;
+                if n == 0 {
+                    break;
+                }
+
+                // The file may have grown since its size was checked.
+                written += n as u64;
+                check_entry_size(max_entry_size, written)?;
+                writer.write_all(&buffer[..n])?;
+                crc.update(&buffer[..n]);
+            }
+
+            Ok((written, crc.sum()))
+        });
+
+        match result {
+            Ok((written, crc)) => {
+                self.entries.lock().update_last(written, crc);
+                Ok(())
+            }
+            Err(error) => {
+                writer
+                    .abort_file()
+                    .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+                self.entries.lock().discard_last();
+                Err(error)
+            }
+        }
+    }
+
+    /// Add a directory entry, with a trailing `/` added to the name if it is missing.
+    ///
+    /// `mode` is the Unix mode of the directory.
+    /// A `ZipInfo` name also sets the timestamp of the entry.
+    pub fn mkdir(&self, name: &Bound<'_, PyAny>, mode: u32) -> PyResult<()> {
+        let mut lock = self.lock_writer()?;
+        let writer = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        self.check_entry_count()?;
+
+        let (mut name, last_modified_time) = if let Ok(name) = name.downcast::<PyString>() {
+            (name.to_cow()?.into_owned(), local_time::now(name.py())?)
+        } else if let Ok(zip_info) = name.extract::<PyRef<'_, ZipInfo>>() {
+            let (year, month, day, hour, minute, second) = zip_info.date_time;
+            let last_modified_time =
+                DateTime::from_date_and_time(year, month, day, hour, minute, second)
+                    .map_err(|error| PyValueError::new_err(error.to_string()))?;
+
+            (zip_info.filename.clone(), last_modified_time)
+        } else {
+            return Err(PyValueError::new_err("name must be a string or ZipInfo"));
+        };
+        // This matches how the zip crate adds the trailing slash.
+        if !name.ends_with(['/', '\\']) {
+            name.push('/');
+        }
+        self.check_name(&name)?;
+
+        // The zip crate rejects duplicate names, including directories added twice.
+        let options = SimpleFileOptions::default()
+            .unix_permissions(mode)
+            .last_modified_time(last_modified_time);
+        let start = self.position.current();
+        writer
+            .add_directory(name.as_str(), options)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        let zip_info = ZipInfo {
+            date_time: date_time_tuple(Some(last_modified_time)),
+            ..ZipInfo::new(&name)
+        };
+        // Directories have no data, so the zip crate finishes them immediately.
+        self.entries
+            .lock()
+            .start(zip_info, start, None, &self.position);
+
+        Ok(())
+    }
+
+    /// Get the names of the members in the archive, including any that is still being written.
+    pub fn namelist(&self) -> Vec<String> {
+        self.entries
+            .lock()
+            .entries
+            .iter()
+            .map(|entry| entry.filename.clone())
+            .collect()
+    }
+
+    /// Get the metadata of the members in the archive, including any that is still being written.
+    pub fn infolist(&self) -> Vec<ZipInfo> {
+        self.entries.lock().entries.clone()
+    }
+
+    /// Get the metadata of a member, raising `KeyError` if no such member was written.
+    pub fn getinfo(&self, name: &str) -> PyResult<ZipInfo> {
+        self.entries
+            .lock()
+            .entries
+            .iter()
+            .find(|entry| entry.filename == name)
+            .cloned()
+            .ok_or_else(|| missing_member(name))
+    }
+
+    /// Check if a member with the given name was written.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries
+            .lock()
+            .entries
+            .iter()
+            .any(|entry| entry.filename == name)
+    }
+
+    /// Get the number of members in the archive, including any that is still being written.
+    pub fn len(&self) -> usize {
+        self.entries.lock().entries.len()
+    }
+
+    /// Get the name of the member at the given index, if there is one.
+    pub fn name_at(&self, index: usize) -> Option<String> {
+        self.entries
+            .lock()
+            .entries
+            .get(index)
+            .map(|entry| entry.filename.clone())
+    }
+
+    /// Write a member whose whole contents are already in memory.
+    fn write_bytes(
+        &self,
+        name: &Bound<'_, PyAny>,
+        data: &[u8],
+        auto_store: bool,
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+    ) -> PyResult<()> {
+        check_entry_size(self.max_entry_size, data.len() as u64)?;
+
+        let large_file = self.large_file(false, Some(data.len() as u64))?;
+        let store = auto_store && is_incompressible(data);
+        let (mut lock, _name) =
+            self.start_file(name, large_file, store, compress_type, compress_level, None)?;
         let writer = lock.as_mut().ok_or_else(|| {
             PyValueError::new_err("Attempt to use ZIP archive that was already closed")
         })?;
 
-        let zip_info = if let Ok(name) = name.downcast::<PyString>() {
-            let name = name.to_cow()?;
+        writer.write_all(data)?;
+
+        let mut crc = Crc::new();
+        crc.update(data);
+        self.entries
+            .lock()
+            .update_last(data.len() as u64, crc.sum());
+
+        Ok(())
+    }
+
+    /// Write a member whose whole contents are in memory, recording `crc` instead of its real CRC.
+    ///
+    /// The zip crate always computes CRCs itself,
+    /// so the member is first written to a temporary archive in memory,
+    /// whose central directory is patched with the given CRC.
+    /// The member is then copied raw, which keeps the CRC of the copied member in both headers.
+    fn write_bytes_with_crc(
+        &self,
+        name: &Bound<'_, PyAny>,
+        data: &[u8],
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+        crc: u32,
+    ) -> PyResult<()> {
+        // A single member and no comment means the record is the last 22 bytes of the archive.
+        const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+
+        check_entry_size(self.max_entry_size, data.len() as u64)?;
+        if self.large_file(false, Some(data.len() as u64))? {
+            return Err(PyValueError::new_err(
+                "override_crc is not supported for members that need ZIP64 extensions",
+            ));
+        }
+
+        let mut lock = self.lock_writer()?;
+        let writer = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        self.check_entry_count()?;
+        let (options, mut zip_info) =
+            self.file_options(name, false, false, compress_type, compress_level, None)?;
+
+        let mut temporary = ZipWriter::new(Cursor::new(Vec::new()));
+        temporary
+            .start_file(zip_info.filename.as_str(), options)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        temporary.write_all(data)?;
+        let mut temporary = temporary
+            .finish()
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?
+            .into_inner();
+
+        let record = temporary.len() - END_OF_CENTRAL_DIRECTORY_SIZE;
+        let central_directory_start = u32::from_le_bytes([
+            temporary[record + 16],
+            temporary[record + 17],
+            temporary[record + 18],
+            temporary[record + 19],
+        ]) as usize;
+        // The CRC is 16 bytes into the central directory header.
+        temporary[central_directory_start + 16..central_directory_start + 20]
+            .copy_from_slice(&crc.to_le_bytes());
+
+        let mut temporary = ZipArchive::new(Cursor::new(temporary))
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        let file = temporary
+            .by_index_raw(0)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        zip_info.compress_size = file.compressed_size();
+        zip_info.file_size = data.len() as u64;
+        zip_info.crc = crc;
+
+        let start = self.position.current();
+        writer
+            .raw_copy_file(file)
+            .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+        self.entries
+            .lock()
+            .push_copied(zip_info, start, &self.position);
+
+        Ok(())
+    }
+
+    /// Decide if a member should be written with Zip64 extensions.
+    ///
+    /// Every way of writing a member goes through this, so that they all make the same choice.
+    /// Members use Zip64 extensions only if forced, or if their known size needs them,
+    /// so members of unknown size are written without them unless forced.
+    /// If Zip64 extensions are needed but `allowZip64` was false, this raises a `LargeZipFile`.
+    /// They are never used when the archive is being written for legacy compatibility.
+    fn large_file(&self, force_zip64: bool, size: Option<u64>) -> PyResult<bool> {
+        if !force_zip64 && size.is_none_or(|size| size <= ZIP64_LIMIT) {
+            return Ok(false);
+        }
+
+        if !self.allow_zip64 {
+            return Err(LargeZipFile::new_err(if force_zip64 {
+                "force_zip64 requires allowZip64 to be true".to_string()
+            } else {
+                format!(
+                    "member size would require ZIP64 extensions, as it exceeds {ZIP64_LIMIT} bytes"
+                )
+            }));
+        }
+
+        Ok(self.compatibility == Compatibility::Modern)
+    }
+
+    /// Check that another member can be added without Zip64 extensions, unless they are allowed.
+    fn check_entry_count(&self) -> PyResult<()> {
+        if !self.allow_zip64 && self.entries.lock().entries.len() >= ZIP64_ENTRY_LIMIT {
+            return Err(LargeZipFile::new_err(format!(
+                "files count would require ZIP64 extensions, as it exceeds {ZIP64_ENTRY_LIMIT}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check that a member name can be written with the archive's compatibility level.
+    ///
+    /// The zip crate flags non-ASCII names as UTF-8, which old unzip tools ignore,
+    /// so they are rejected when writing for legacy compatibility.
+    fn check_name(&self, name: &str) -> PyResult<()> {
+        if self.compatibility == Compatibility::Legacy && !name.is_ascii() {
+            return Err(PyValueError::new_err(format!(
+                "non-ASCII name {name:?} is not supported with legacy compatibility"
+            )));
+        }
+
+        Ok(())
+    }
 
-            let mut zip_info = ZipInfo::new(&name);
-            zip_info.compress_type = u8::from(self.compression_kind);
+    /// Lock the writer, failing if a member is still being written through an open handle.
+    fn lock_writer(&self) -> PyResult<WriterGuard> {
+        self.file.try_lock_arc().ok_or_else(|| {
+            PyRuntimeError::new_err(
+                "Cannot open another file handle while another file handle is still open",
+            )
+        })
+    }
+
+    /// Get the options to write a member with, and the `ZipInfo` to record for it.
+    ///
+    /// `large_file` forces Zip64 extensions for the member,
+    /// and `store` stores it regardless of the requested compression.
+    /// `compress_type` and `compress_level` override the compression picked from `name`,
+    /// and `permissions` are the Unix permissions of the member, if known.
+    fn file_options(
+        &self,
+        name: &Bound<'_, PyAny>,
+        large_file: bool,
+        store: bool,
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+        permissions: Option<u32>,
+    ) -> PyResult<(SimpleFileOptions, ZipInfo)> {
+        let mut options = SimpleFileOptions::default();
+        let mut zip_info = if let Ok(name) = name.downcast::<PyString>() {
+            let mut zip_info = ZipInfo::new(&name.to_cow()?);
+            zip_info.compress_type = u16::from(self.compression_kind);
             zip_info.compress_level = self.compression_level;
 
+            // Other members take the current time, which is recorded for `infolist`.
+            let last_modified_time = local_time::now(name.py())?;
+            options = options.last_modified_time(last_modified_time);
+            zip_info.date_time = date_time_tuple(Some(last_modified_time));
+
             zip_info
         } else if let Ok(zip_info) = name.extract::<PyRef<'_, ZipInfo>>() {
+            // Only a ZipInfo carries an explicit timestamp.
+            // Otherwise, the current time is used.
+            let (year, month, day, hour, minute, second) = zip_info.date_time;
+            let last_modified_time =
+                DateTime::from_date_and_time(year, month, day, hour, minute, second)
+                    .map_err(|error| PyValueError::new_err(error.to_string()))?;
+            options = options.last_modified_time(last_modified_time);
+
             zip_info.clone()
         } else {
             return Err(PyValueError::new_err("name must be a string or ZipInfo"));
         };
+        self.check_name(&zip_info.filename)?;
+        if let Some(compress_type) = compress_type {
+            zip_info.compress_type = compress_type;
+        }
+        if let Some(compress_level) = compress_level {
+            zip_info.compress_level = Some(compress_level);
+        }
 
-        let mut options = SimpleFileOptions::default();
         let compression_kind = CompressionKind::try_from(zip_info.compress_type)?;
+        if self.compatibility == Compatibility::Legacy
+            && !matches!(
+                compression_kind,
+                CompressionKind::Stored | CompressionKind::Deflated
+            )
+        {
+            return Err(PyValueError::new_err(format!(
+                "compression type {} is not supported with legacy compatibility",
+                zip_info.compress_type
+            )));
+        }
+        let compression_kind = if store {
+            CompressionKind::Stored
+        } else {
+            compression_kind
+        };
+        zip_info.compress_type = u16::from(compression_kind);
         match compression_kind {
             CompressionKind::Stored => {
                 options = options.compression_method(zip::CompressionMethod::Stored);
             }
             CompressionKind::Deflated => {
                 options = options.compression_method(zip::CompressionMethod::Deflated);
-                if let Some(compression_level) = zip_info.compress_level {
-                    if !(0..=9).contains(&compression_level) {
-                        return Err(PyValueError::new_err(format!(
-                            "invalid ZIP_DEFLATED compresslevel {compression_level}"
-                        )));
+                match self.deflate_backend {
+                    DeflateBackend::Zlib => {
+                        if let Some(compression_level) = zip_info.compress_level {
+                            if !(0..=9).contains(&compression_level) {
+                                return Err(PyValueError::new_err(format!(
+                                    "invalid ZIP_DEFLATED compresslevel {compression_level}"
+                                )));
+                            }
+
+                            options = options.compression_level(Some(compression_level.into()));
+                        }
                     }
+                    DeflateBackend::Zopfli => {
+                        let iterations =
+                            zip_info.compress_level.unwrap_or(ZOPFLI_DEFAULT_ITERATIONS);
+                        if iterations == 0 {
+                            return Err(PyValueError::new_err(format!(
+                                "invalid zopfli ZIP_DEFLATED compresslevel {iterations}"
+                            )));
+                        }
 
-                    options = options.compression_level(Some(compression_level.into()));
+                        // The zip crate uses zopfli for levels above 9,
+                        // with an iteration count of the level minus 9.
+                        options = options.compression_level(Some(9 + i64::from(iterations)));
+                    }
                 }
             }
+            CompressionKind::Deflate64 => {
+                return Err(PyNotImplementedError::new_err(
+                    "writing ZIP_DEFLATED64 is currently unsupported",
+                ));
+            }
             CompressionKind::Bzip2 => {
                 options = options.compression_method(zip::CompressionMethod::Bzip2);
                 if let Some(compression_level) = zip_info.compress_level {
@@ -105,30 +776,338 @@ impl WriteZipFile {
             }
         }
 
+        // With legacy compatibility, the zip crate will error instead of writing Zip64 extensions.
+        options = options.large_file(large_file);
+        if let Some(permissions) = permissions {
+            options = options.unix_permissions(permissions);
+        }
+
+        Ok((options, zip_info))
+    }
+
+    /// Start a new member, returning the locked writer and the member name.
+    ///
+    /// The arguments are described on `file_options`.
+    fn start_file(
+        &self,
+        name: &Bound<'_, PyAny>,
+        large_file: bool,
+        store: bool,
+        compress_type: Option<u16>,
+        compress_level: Option<u8>,
+        permissions: Option<u32>,
+    ) -> PyResult<(WriterGuard, String)> {
+        let mut lock = self.lock_writer()?;
+        let writer = lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZIP archive that was already closed")
+        })?;
+
+        self.check_entry_count()?;
+        let (options, zip_info) = self.file_options(
+            name,
+            large_file,
+            store,
+            compress_type,
+            compress_level,
+            permissions,
+        )?;
+
+        let start = self.position.current();
         writer
-            .start_file(zip_info.filename, options)
+            .start_file(zip_info.filename.as_str(), options)
             .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
 
-        Ok(WriteZipExtFile { lock })
+        let name = zip_info.filename.clone();
+        // The member's data starts right after the header the zip crate just wrote.
+        let data_start = self.position.current();
+        self.entries
+            .lock()
+            .start(zip_info, start, Some(data_start), &self.position);
+
+        Ok((lock, name))
+    }
+}
+
+/// Check if data looks incompressible, from the byte entropy of its start.
+///
+/// Empty data is never considered incompressible.
+fn is_incompressible(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(AUTO_STORE_SAMPLE_SIZE)];
+    if sample.is_empty() {
+        return false;
+    }
+
+    let mut counts = [0_usize; 256];
+    for &byte in sample {
+        counts[usize::from(byte)] += 1;
     }
+
+    let len = sample.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let probability = count as f64 / len;
+            -probability * probability.log2()
+        })
+        .sum();
+
+    entropy > AUTO_STORE_ENTROPY
 }
 
+/// Get the metadata of the members of an existing archive, before appending to it.
+fn existing_entries(file: &mut ArchiveFile) -> PyResult<Vec<ZipInfo>> {
+    let mut archive =
+        ZipArchive::new(file).map_err(|error| BadZipFile::new_err(error.to_string()))?;
+
+    (0..archive.len())
+        .map(|index| {
+            let file = archive
+                .by_index_raw(index)
+                .map_err(|error| BadZipFile::new_err(error.to_string()))?;
+
+            Ok(ZipInfo::from_zip_file(&file))
+        })
+        .collect()
+}
+
+/// The members of an archive being written, for listing them before it is closed.
+#[derive(Debug, Default)]
+struct WrittenEntries {
+    entries: Vec<ZipInfo>,
+    /// Where the data of the last member starts, if the zip crate hasn't finished it yet.
+    ///
+    /// The zip crate finishes a member when the next one is started or the archive is closed,
+    /// which is when its compressed size is known.
+    /// Until then, the compressed size is zero.
+    unfinished_data_start: Option<u64>,
+}
+
+impl WrittenEntries {
+    fn new(entries: Vec<ZipInfo>) -> Self {
+        Self {
+            entries,
+            unfinished_data_start: None,
+        }
+    }
+
+    /// Record a member that the zip crate just started, which also finishes the previous one.
+    ///
+    /// `start` is the position of the writer before starting the member,
+    /// and `data_start` is where its data starts, if it has any.
+    fn start(
+        &mut self,
+        zip_info: ZipInfo,
+        start: u64,
+        data_start: Option<u64>,
+        position: &WritePosition,
+    ) {
+        // Finishing the previous member writes the rest of its data, so the header comes after it.
+        let header_offset = self.finish_last(position).unwrap_or(start);
+        self.entries.push(ZipInfo {
+            compress_size: 0,
+            file_size: 0,
+            crc: 0,
+            header_offset,
+            ..zip_info
+        });
+        self.unfinished_data_start = data_start;
+    }
+
+    /// Record a member that the zip crate copied in full, with its final sizes and CRC,
+    /// which also finishes the previous one.
+    ///
+    /// `start` is the position of the writer before copying the member.
+    fn push_copied(&mut self, zip_info: ZipInfo, start: u64, position: &WritePosition) {
+        let header_offset = self.finish_last(position).unwrap_or(start);
+        self.entries.push(ZipInfo {
+            header_offset,
+            ..zip_info
+        });
+    }
+
+    /// Record the compressed size of the last member, after the zip crate finished it.
+    ///
+    /// Returns where its data ended, if it was unfinished.
+    fn finish_last(&mut self, position: &WritePosition) -> Option<u64> {
+        let data_start = self.unfinished_data_start.take()?;
+        // The zip crate seeks back to the local header of a finished member to fill in its sizes.
+        let data_end = position.rewound_from();
+        if let Some(entry) = self.entries.last_mut() {
+            entry.compress_size = data_end.saturating_sub(data_start);
+        }
+
+        Some(data_end)
+    }
+
+    /// Record the size and CRC of the member being written, which is always the last one.
+    fn update_last(&mut self, size: u64, crc: u32) {
+        if let Some(entry) = self.entries.last_mut() {
+            entry.file_size = size;
+            entry.crc = crc;
+        }
+    }
+
+    /// Forget the last member, after the zip crate aborted it.
+    fn discard_last(&mut self) {
+        self.entries.pop();
+        self.unfinished_data_start = None;
+    }
+}
+
+/// The position of an archive writer.
+#[derive(Debug, Default)]
+struct WritePosition {
+    current: AtomicU64,
+    /// The position the writer was at before it last seeked backwards.
+    rewound_from: AtomicU64,
+}
+
+impl WritePosition {
+    fn current(&self) -> u64 {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    fn rewound_from(&self) -> u64 {
+        self.rewound_from.load(Ordering::Relaxed)
+    }
+}
+
+/// An archive file that shares its position, so that the offsets of members can be recorded.
+///
+/// The zip crate doesn't expose the position of its writer, nor the offsets of written members.
+#[derive(Debug)]
+struct TrackedFile {
+    file: ArchiveFile,
+    position: Arc<WritePosition>,
+}
+
+impl TrackedFile {
+    /// Wrap a file, returning the position it shares.
+    fn new(mut file: ArchiveFile) -> PyResult<(Self, Arc<WritePosition>)> {
+        let position = Arc::new(WritePosition {
+            current: AtomicU64::new(file.stream_position()?),
+            rewound_from: AtomicU64::new(0),
+        });
+
+        Ok((
+            Self {
+                file,
+                position: position.clone(),
+            },
+            position,
+        ))
+    }
+}
+
+impl Read for TrackedFile {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.file.read(buf)?;
+        self.position.current.fetch_add(n as u64, Ordering::Relaxed);
+
+        Ok(n)
+    }
+}
+
+impl Write for TrackedFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.file.write(buf)?;
+        self.position.current.fetch_add(n as u64, Ordering::Relaxed);
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Seek for TrackedFile {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let previous = self.position.current();
+        let current = self.file.seek(pos)?;
+        if current < previous {
+            self.position
+                .rewound_from
+                .store(previous, Ordering::Relaxed);
+        }
+        self.position.current.store(current, Ordering::Relaxed);
+
+        Ok(current)
+    }
+}
+
+/// Fail if a member has grown past the maximum entry size.
+fn check_entry_size(max_entry_size: Option<u64>, size: u64) -> PyResult<()> {
+    match max_entry_size {
+        Some(max_entry_size) if size > max_entry_size => Err(PyValueError::new_err(format!(
+            "member exceeds max_entry_size of {max_entry_size} bytes"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+type WriterGuard = ArcMutexGuard<parking_lot::RawMutex, Option<ZipWriter<TrackedFile>>>;
+
 pub(crate) struct WriteZipExtFile {
-    lock: ArcMutexGuard<parking_lot::RawMutex, Option<ZipWriter<File>>>,
+    name: String,
+    lock: Option<WriterGuard>,
+    written: u64,
+    crc: Crc,
+    max_entry_size: Option<u64>,
+    entries: Arc<Mutex<WrittenEntries>>,
 }
 
 impl WriteZipExtFile {
+    /// Get the name of the member.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check if the member was closed.
+    pub(crate) fn closed(&self) -> bool {
+        self.lock.is_none()
+    }
+
+    /// Get the number of bytes written so far.
+    pub(crate) fn tell(&self) -> u64 {
+        self.written
+    }
+
     pub(crate) fn write(&mut self, buffer: &[u8]) -> PyResult<()> {
-        let writer = self.lock.as_mut().ok_or_else(|| {
+        let lock = self.lock.as_mut().ok_or_else(|| {
+            PyValueError::new_err("Attempt to use ZipExtFile that was already closed")
+        })?;
+        let writer = lock.as_mut().ok_or_else(|| {
             PyValueError::new_err("Attempt to use ZIP archive that was already closed")
         })?;
 
+        // Going over the limit discards the member, and closes the handle.
+        self.written += buffer.len() as u64;
+        if let Err(error) = check_entry_size(self.max_entry_size, self.written) {
+            writer
+                .abort_file()
+                .map_err(|error| PyRuntimeError::new_err(error.to_string()))?;
+            self.entries.lock().discard_last();
+            self.close();
+            return Err(error);
+        }
+
         writer.write_all(buffer)?;
+        self.crc.update(buffer);
+        self.entries
+            .lock()
+            .update_last(self.written, self.crc.sum());
 
         Ok(())
     }
 
-    pub(crate) fn close(&mut self) {}
+    pub(crate) fn close(&mut self) {
+        // Release the archive lock, so the archive may be closed or another handle opened.
+        if let Some(lock) = self.lock.take() {
+            drop(lock);
+        }
+    }
 
     pub(crate) fn __exit__(&mut self) {
         self.close();
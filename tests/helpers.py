@@ -0,0 +1,106 @@
+"""Helpers for building archives to test against."""
+
+from __future__ import annotations
+
+import contextlib
+import dataclasses
+import io
+import os
+import struct
+import time
+import zipfile
+import zlib
+from typing import Iterator
+
+# The America/New_York rules as a POSIX TZ string, which needs no time zone database.
+NEW_YORK = "EST+05EDT,M3.2.0,M11.1.0"
+
+
+def make_archive(
+    entries: dict[str, bytes],
+    compression: int = zipfile.ZIP_STORED,
+) -> io.BytesIO:
+    """Build an archive in memory with the stdlib, with members in the given order."""
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w", compression) as archive:
+        for name, data in entries.items():
+            archive.writestr(name, data)
+
+    buffer.seek(0)
+    return buffer
+
+
+@dataclasses.dataclass
+class RawMember:
+    """A member of an archive built by hand, with its data already compressed."""
+
+    name: str
+    data: bytes
+    compressed: bytes
+    compress_type: int
+    flag_bits: int = 0
+
+
+def make_raw_archive(members: list[RawMember]) -> io.BytesIO:
+    """Build an archive by hand, for members the stdlib can't write."""
+    local_records = b""
+    central_records = b""
+    for member in members:
+        name = member.name.encode("ascii")
+        # Version 2.1 is enough to extract Deflate64 and ZipCrypto,
+        # and the date field holds 1980-01-01.
+        fields = (
+            21,
+            member.flag_bits,
+            member.compress_type,
+            0,
+            0x21,
+            zlib.crc32(member.data),
+            len(member.compressed),
+            len(member.data),
+            len(name),
+            0,
+        )
+        central_records += struct.pack(
+            "<4s6HL2L5H2L",
+            b"PK\x01\x02",
+            21,
+            *fields,
+            0,
+            0,
+            0,
+            0,
+            len(local_records),
+        )
+        central_records += name
+        local_records += struct.pack("<4s5HL2L2H", b"PK\x03\x04", *fields)
+        local_records += name + member.compressed
+
+    end_record = struct.pack(
+        "<4s4H2LH",
+        b"PK\x05\x06",
+        0,
+        0,
+        len(members),
+        len(members),
+        len(central_records),
+        len(local_records),
+        0,
+    )
+    return io.BytesIO(local_records + central_records + end_record)
+
+
+@contextlib.contextmanager
+def local_timezone(timezone: str) -> Iterator[None]:
+    """Change the local time zone of the process, which zip timestamps are taken in."""
+    previous = os.environ.get("TZ")
+    os.environ["TZ"] = timezone
+    time.tzset()
+    try:
+        yield
+    finally:
+        if previous is None:
+            del os.environ["TZ"]
+        else:
+            os.environ["TZ"] = previous
+        time.tzset()
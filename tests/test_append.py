@@ -0,0 +1,62 @@
+from __future__ import annotations
+
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+
+EXISTING = {"a.txt": b"alpha" * 100, "dir/b.bin": bytes(range(256))}
+APPENDED = {"c.txt": b"gamma", "dir/d.txt": b"delta" * 50}
+COMMENT = b"an archive comment"
+
+
+def write_existing(file: str | io.BytesIO) -> None:
+    with zipfile.ZipFile(file, "w", zipfile.ZIP_DEFLATED) as archive:
+        archive.comment = COMMENT
+        for name, data in EXISTING.items():
+            archive.writestr(name, data)
+
+
+class AppendTests(unittest.TestCase):
+    def append_and_check(self, file: str | io.BytesIO) -> None:
+        with nd_zipfile.ZipFile(file, "a", nd_zipfile.ZIP_DEFLATED) as archive:
+            for name, data in APPENDED.items():
+                archive.writestr(name, data)
+
+        expected = {**EXISTING, **APPENDED}
+        with zipfile.ZipFile(file) as archive:
+            self.assertIsNone(archive.testzip())
+            self.assertEqual(archive.namelist(), list(expected))
+            self.assertEqual({name: archive.read(name) for name in expected}, expected)
+            self.assertEqual(archive.comment, COMMENT)
+
+        with nd_zipfile.ZipFile(file, "r") as archive:
+            self.assertEqual(archive.namelist(), list(expected))
+            self.assertEqual({name: archive.read(name) for name in expected}, expected)
+
+    def test_path(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "archive.zip")
+            write_existing(path)
+            self.append_and_check(path)
+
+    def test_file_like(self) -> None:
+        buffer = io.BytesIO()
+        write_existing(buffer)
+        self.append_and_check(buffer)
+
+    def test_new_path(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "new.zip")
+            with nd_zipfile.ZipFile(path, "a") as archive:
+                archive.writestr("c.txt", b"gamma")
+
+            with zipfile.ZipFile(path) as archive:
+                self.assertEqual(archive.read("c.txt"), b"gamma")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,54 @@
+import io
+import os
+import unittest
+import zipfile
+
+import nd_zipfile
+
+RANDOM = os.urandom(64 * 1024)
+TEXT = b"the quick brown fox jumps over the lazy dog\n" * 1500
+
+
+class AutoStoreTests(unittest.TestCase):
+    def check_methods(self, buffer: io.BytesIO) -> None:
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.testzip())
+            self.assertEqual(archive.read("random.bin"), RANDOM)
+            self.assertEqual(archive.read("text.txt"), TEXT)
+            self.assertEqual(
+                archive.getinfo("random.bin").compress_type, zipfile.ZIP_STORED
+            )
+            self.assertEqual(
+                archive.getinfo("text.txt").compress_type, zipfile.ZIP_DEFLATED
+            )
+
+    def test_write_all(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.write_all({"random.bin": RANDOM, "text.txt": TEXT}, auto_store=True)
+
+        self.check_methods(buffer)
+
+    def test_write_iter(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.write_iter(
+                "random.bin", [RANDOM[:4096], RANDOM[4096:]], auto_store=True
+            )
+            archive.write_iter("text.txt", [TEXT[:4096], TEXT[4096:]], auto_store=True)
+
+        self.check_methods(buffer)
+
+    def test_off_by_default(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.write_all({"random.bin": RANDOM})
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(
+                archive.getinfo("random.bin").compress_type, zipfile.ZIP_DEFLATED
+            )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,59 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+
+class CloseTests(unittest.TestCase):
+    def test_read_archive_closes_twice(self) -> None:
+        archive = nd_zipfile.ZipFile(make_archive({"a.txt": b"alpha"}), "r")
+        archive.close()
+        archive.close()
+        with self.assertRaises(ValueError):
+            archive.read("a.txt")
+
+    def test_write_archive_closes_twice(self) -> None:
+        buffer = io.BytesIO()
+        archive = nd_zipfile.ZipFile(buffer, "w")
+        archive.writestr("a.txt", b"alpha")
+        archive.close()
+        archive.close()
+
+        # The second close doesn't write the central directory again.
+        with zipfile.ZipFile(buffer) as stdlib_archive:
+            self.assertIsNone(stdlib_archive.testzip())
+            self.assertEqual(stdlib_archive.namelist(), ["a.txt"])
+
+    def test_context_manager_after_close(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({"a.txt": b"alpha"}), "r") as archive:
+            archive.close()
+
+    def test_read_member_closes_twice(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({"a.txt": b"alpha"}), "r") as archive:
+            handle = archive.open("a.txt")
+            handle.close()
+            handle.close()
+            self.assertTrue(handle.closed)
+
+            # The archive is free for the next member.
+            self.assertEqual(archive.read("a.txt"), b"alpha")
+
+    def test_write_member_closes_twice(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            handle = archive.open("a.txt", "w")
+            handle.write(b"alpha")
+            handle.close()
+            handle.close()
+            self.assertTrue(handle.closed)
+            archive.writestr("b.txt", b"beta")
+
+        with zipfile.ZipFile(buffer) as stdlib_archive:
+            self.assertEqual(stdlib_archive.read("a.txt"), b"alpha")
+            self.assertEqual(stdlib_archive.read("b.txt"), b"beta")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,63 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+DATA_DESCRIPTOR_FLAG = 0x08
+UTF8_FLAG = 0x800
+
+
+class LegacyCompatibilityTests(unittest.TestCase):
+    def test_legacy_archive_has_no_modern_records(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(
+            buffer, "w", nd_zipfile.ZIP_DEFLATED, compatibility="legacy"
+        ) as archive:
+            archive.writestr("writestr.txt", b"from bytes" * 100)
+            with archive.open("open.txt", "w") as handle:
+                handle.write(b"from a handle")
+            archive.write_iter("write_iter.txt", [b"from ", b"chunks"])
+            archive.mkdir("directory")
+
+        data = buffer.getvalue()
+        # Zip64 end of central directory record and locator signatures.
+        self.assertNotIn(b"PK\x06\x06", data)
+        self.assertNotIn(b"PK\x06\x07", data)
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.testzip())
+            infos = archive.infolist()
+            self.assertEqual(len(infos), 4)
+            for info in infos:
+                self.assertLess(info.extract_version, zipfile.ZIP64_VERSION)
+                self.assertEqual(info.flag_bits & DATA_DESCRIPTOR_FLAG, 0)
+                self.assertEqual(info.flag_bits & UTF8_FLAG, 0)
+
+    def test_legacy_rejects_non_ascii_names(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", compatibility="legacy") as archive:
+            with self.assertRaises(ValueError):
+                archive.writestr("café.txt", b"data")
+            with self.assertRaises(ValueError):
+                archive.mkdir("café")
+            self.assertEqual(archive.namelist(), [])
+
+    def test_modern_flags_non_ascii_names_as_utf8(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("café.txt", b"data")
+
+        with zipfile.ZipFile(buffer) as archive:
+            info = archive.getinfo("café.txt")
+            self.assertEqual(info.flag_bits & UTF8_FLAG, UTF8_FLAG)
+
+    def test_legacy_rejects_other_compression_methods(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", compatibility="legacy") as archive:
+            with self.assertRaises(ValueError):
+                archive.writestr("a.txt", b"data", nd_zipfile.ZIP_BZIP2)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,32 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {"a.txt": b"alpha", "dir/b.txt": b"beta"}
+
+
+class ContainsTests(unittest.TestCase):
+    def test_membership(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            self.assertIn("a.txt", archive)
+            self.assertIn("dir/b.txt", archive)
+            self.assertNotIn("dir", archive)
+            self.assertNotIn("missing.txt", archive)
+
+    def test_membership_while_a_member_is_open(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            with archive.open("a.txt") as handle:
+                self.assertIn("dir/b.txt", archive)
+                self.assertNotIn("missing.txt", archive)
+                self.assertEqual(handle.read(), ENTRIES["a.txt"])
+
+    def test_closed_archive_raises(self) -> None:
+        archive = nd_zipfile.ZipFile(make_archive(ENTRIES), "r")
+        archive.close()
+        with self.assertRaises(ValueError):
+            self.assertIn("a.txt", archive)
+
+
+if __name__ == "__main__":
+    unittest.main()
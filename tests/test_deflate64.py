@@ -0,0 +1,98 @@
+from __future__ import annotations
+
+import io
+import random
+import unittest
+import zipfile
+import zlib
+
+import nd_zipfile
+from tests.helpers import RawMember, make_raw_archive
+
+# Longer than the 32 KiB window of Deflate, so copying it back needs Deflate64.
+PREFIX = bytes(random.Random(0).getrandbits(8) for _ in range(40000))
+DATA = PREFIX + PREFIX[:100]
+
+
+class BitWriter:
+    def __init__(self) -> None:
+        self.output: list[int] = []
+
+    def bits(self, value: int, count: int) -> None:
+        """Write a number least significant bit first, like header and extra bits."""
+        self.output.extend((value >> shift) & 1 for shift in range(count))
+
+    def code(self, value: int, count: int) -> None:
+        """Write a Huffman code most significant bit first."""
+        self.output.extend((value >> shift) & 1 for shift in reversed(range(count)))
+
+    def getvalue(self) -> bytes:
+        output = bytearray((len(self.output) + 7) // 8)
+        for index, bit in enumerate(self.output):
+            output[index // 8] |= bit << (index % 8)
+        return bytes(output)
+
+
+def deflate64_with_fixed_codes() -> bytes:
+    """Encode `DATA` with fixed Huffman codes, ending with a match 40000 bytes back."""
+    writer = BitWriter()
+    # A final block with fixed Huffman codes.
+    writer.bits(1, 1)
+    writer.bits(1, 2)
+    for byte in PREFIX:
+        if byte < 144:
+            writer.code(0x30 + byte, 8)
+        else:
+            writer.code(0x190 + byte - 144, 9)
+
+    # Length code 279 covers lengths 99 to 114, and 100 is its first extra bit value.
+    writer.code(279 - 256, 7)
+    writer.bits(100 - 99, 4)
+    # Distance code 30 covers distances 32769 to 49152, and only exists in Deflate64.
+    writer.code(30, 5)
+    writer.bits(40000 - 32769, 14)
+
+    # The end of block code.
+    writer.code(0, 7)
+    return writer.getvalue()
+
+
+class Deflate64Tests(unittest.TestCase):
+    def test_read(self) -> None:
+        member = RawMember(
+            "data.bin", DATA, deflate64_with_fixed_codes(), nd_zipfile.ZIP_DEFLATED64
+        )
+        buffer = make_raw_archive([member])
+        with zipfile.ZipFile(buffer) as archive:
+            info = archive.getinfo("data.bin")
+            self.assertEqual(info.compress_type, nd_zipfile.ZIP_DEFLATED64)
+            self.assertEqual(info.file_size, len(DATA))
+
+        # Plain Deflate has no code for such a long distance.
+        with self.assertRaises(zlib.error):
+            zlib.decompress(deflate64_with_fixed_codes(), -15)
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(
+                archive.getinfo("data.bin").compress_type, nd_zipfile.ZIP_DEFLATED64
+            )
+            self.assertEqual(archive.read("data.bin"), DATA)
+            with archive.open("data.bin") as handle:
+                self.assertEqual(handle.read(50), DATA[:50])
+                self.assertEqual(handle.read(), DATA[50:])
+            self.assertIsNone(archive.testzip())
+
+    def test_write_is_unsupported(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            with self.assertRaisesRegex(NotImplementedError, "ZIP_DEFLATED64"):
+                archive.writestr("a.txt", b"alpha", nd_zipfile.ZIP_DEFLATED64)
+            with self.assertRaisesRegex(NotImplementedError, "ZIP_DEFLATED64"):
+                archive.writestr(
+                    "b.txt", b"beta", compress_type=nd_zipfile.ZIP_DEFLATED64
+                )
+            self.assertEqual(archive.namelist(), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
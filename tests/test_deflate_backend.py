@@ -0,0 +1,32 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+DATA = b"The quick brown fox jumps over the lazy dog. " * 200
+
+
+class DeflateBackendTests(unittest.TestCase):
+    def test_each_backend_is_readable_by_the_stdlib(self) -> None:
+        for backend in ["zlib", "zopfli"]:
+            with self.subTest(backend=backend):
+                buffer = io.BytesIO()
+                with nd_zipfile.ZipFile(
+                    buffer, "w", nd_zipfile.ZIP_DEFLATED, deflate_backend=backend
+                ) as archive:
+                    archive.writestr("data.txt", DATA)
+
+                with zipfile.ZipFile(buffer) as archive:
+                    info = archive.getinfo("data.txt")
+                    self.assertEqual(info.compress_type, zipfile.ZIP_DEFLATED)
+                    self.assertLess(info.compress_size, len(DATA))
+                    self.assertEqual(archive.read("data.txt"), DATA)
+
+    def test_unknown_backend_is_rejected(self) -> None:
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile(io.BytesIO(), "w", deflate_backend="miniz")
+
+
+if __name__ == "__main__":
+    unittest.main()
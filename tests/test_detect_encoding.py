@@ -0,0 +1,55 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+JAPANESE_NAMES = ["日本語.txt", "写真/夏休み.jpg", "資料/会議の議事録.docx"]
+
+
+def make_legacy_archive(names: list[str], encoding: str) -> io.BytesIO:
+    """Build an archive with names in a legacy encoding, without the UTF-8 flag."""
+    encoded = [name.encode(encoding) for name in names]
+    # The stdlib only writes cp437 or UTF-8 names,
+    # so write placeholders of the same length and swap the bytes in afterwards.
+    placeholders = [
+        f"{index:0{len(name)}d}".encode() for index, name in enumerate(encoded)
+    ]
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        for placeholder in placeholders:
+            archive.writestr(placeholder.decode(), b"data")
+
+    data = buffer.getvalue()
+    for placeholder, name in zip(placeholders, encoded):
+        data = data.replace(placeholder, name)
+
+    return io.BytesIO(data)
+
+
+class DetectEncodingTests(unittest.TestCase):
+    def test_cp932_names(self) -> None:
+        buffer = make_legacy_archive(JAPANESE_NAMES, "cp932")
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertFalse(any(info.flag_bits & 0x800 for info in archive.infolist()))
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            encoding, confidence = archive.detect_encoding()
+
+        self.assertEqual(encoding, "cp932")
+        self.assertEqual(confidence, 1.0)
+
+    def test_ascii_names(self) -> None:
+        buffer = make_archive({"a.txt": b"a", "dir/b.txt": b"b"})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(archive.detect_encoding(), ("ascii", 1.0))
+
+    def test_utf8_names(self) -> None:
+        buffer = make_archive({name: b"data" for name in JAPANESE_NAMES})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(archive.detect_encoding(), ("utf-8", 1.0))
+
+
+if __name__ == "__main__":
+    unittest.main()
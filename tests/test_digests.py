@@ -0,0 +1,51 @@
+import hashlib
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+DATA = bytes(range(256)) * 1000
+
+
+class DigestTests(unittest.TestCase):
+    def setUp(self) -> None:
+        buffer = make_archive({"data.bin": DATA}, zipfile.ZIP_DEFLATED)
+        self.archive = nd_zipfile.ZipFile(buffer, "r")
+        self.addCleanup(self.archive.close)
+
+    def test_read_verified_matching_digest(self) -> None:
+        digest = hashlib.sha256(DATA).hexdigest()
+        self.assertEqual(self.archive.read_verified("data.bin", digest), DATA)
+        self.assertEqual(self.archive.read_verified("data.bin", digest.upper()), DATA)
+
+        info = self.archive.getinfo("data.bin")
+        self.assertEqual(self.archive.read_verified(info, digest), DATA)
+
+    def test_read_verified_mismatching_digest(self) -> None:
+        digest = hashlib.sha256(b"other data").hexdigest()
+        with self.assertRaisesRegex(ValueError, "data.bin"):
+            self.archive.read_verified("data.bin", digest)
+
+        # The archive is released after the failed read.
+        self.assertEqual(self.archive.read("data.bin"), DATA)
+
+    def test_digests_match_hashlib(self) -> None:
+        algorithms = ["md5", "sha1", "sha256"]
+        self.assertEqual(
+            self.archive.digests("data.bin", algorithms),
+            {
+                algorithm: hashlib.new(algorithm, DATA).hexdigest()
+                for algorithm in algorithms
+            },
+        )
+
+    def test_digests_unknown_algorithm(self) -> None:
+        with self.assertRaises(ValueError):
+            self.archive.digests("data.bin", ["not-a-hash"])
+
+        self.assertEqual(self.archive.digests("data.bin", []), {})
+
+
+if __name__ == "__main__":
+    unittest.main()
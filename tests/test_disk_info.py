@@ -0,0 +1,34 @@
+import io
+import unittest
+import zipfile
+from unittest import mock
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+
+class DiskInfoTests(unittest.TestCase):
+    def test_single_disk_defaults(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({"a.txt": b"alpha"}), "r") as archive:
+            # Classic archives don't record the number of disks.
+            self.assertEqual(
+                archive.disk_info(),
+                {"disk_number": 0, "disk_with_central_directory": 0},
+            )
+
+    def test_zip64_records_total_disks(self) -> None:
+        buffer = io.BytesIO()
+        # Make the stdlib think the archive has too many members for a classic record.
+        with mock.patch.object(zipfile, "ZIP_FILECOUNT_LIMIT", 0):
+            with zipfile.ZipFile(buffer, "w") as archive:
+                archive.writestr("a.txt", b"alpha")
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(
+                archive.disk_info(),
+                {"disk_number": 0, "disk_with_central_directory": 0, "total_disks": 1},
+            )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,40 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+
+class DominantCompressionTest(unittest.TestCase):
+    def test_majority_method_wins(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("a", b"a", zipfile.ZIP_DEFLATED)
+            archive.writestr("b", b"b", zipfile.ZIP_STORED)
+            archive.writestr("c", b"c", zipfile.ZIP_BZIP2)
+            archive.writestr("d", b"d", zipfile.ZIP_DEFLATED)
+            archive.writestr("e", b"e", zipfile.ZIP_DEFLATED)
+
+        with nd_zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.dominant_compression(), nd_zipfile.ZIP_DEFLATED)
+
+    def test_ties_go_to_the_lowest_method(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("a", b"a", zipfile.ZIP_DEFLATED)
+            archive.writestr("b", b"b", zipfile.ZIP_STORED)
+
+        with nd_zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.dominant_compression(), nd_zipfile.ZIP_STORED)
+
+    def test_empty_archive_has_none(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w"):
+            pass
+
+        with nd_zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.dominant_compression())
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,44 @@
+import io
+import unittest
+import warnings
+import zipfile
+
+import nd_zipfile
+
+
+def make_duplicate_archive() -> io.BytesIO:
+    buffer = io.BytesIO()
+    with warnings.catch_warnings():
+        # The stdlib warns about duplicate names, but writes them anyway.
+        warnings.simplefilter("ignore", UserWarning)
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("a.txt", b"first")
+            archive.writestr("b.txt", b"beta")
+            archive.writestr("a.txt", b"second, longer")
+
+    buffer.seek(0)
+    return buffer
+
+
+class DuplicateNameTests(unittest.TestCase):
+    def test_fixture_has_duplicates(self) -> None:
+        with zipfile.ZipFile(make_duplicate_archive()) as archive:
+            self.assertEqual(archive.namelist(), ["a.txt", "b.txt", "a.txt"])
+
+    def test_duplicates_are_listed_once_at_the_first_position(self) -> None:
+        with nd_zipfile.ZipFile(make_duplicate_archive(), "r") as archive:
+            self.assertEqual(archive.namelist(), ["a.txt", "b.txt"])
+            self.assertEqual(len(archive), 2)
+
+    def test_last_entry_wins(self) -> None:
+        with nd_zipfile.ZipFile(make_duplicate_archive(), "r") as archive:
+            info = archive.getinfo("a.txt")
+            self.assertEqual(info.file_size, len(b"second, longer"))
+            self.assertEqual(archive.read("a.txt"), b"second, longer")
+            with archive.open("a.txt") as handle:
+                self.assertEqual(handle.read(), b"second, longer")
+            self.assertEqual(archive.read("b.txt"), b"beta")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,36 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+DATA = b"x" * 1000
+
+
+class ExpectedSizeTests(unittest.TestCase):
+    def setUp(self) -> None:
+        self.archive = nd_zipfile.ZipFile(make_archive({"a.txt": DATA}), "r")
+        self.addCleanup(self.archive.close)
+
+    def test_matching_size_opens(self) -> None:
+        for lazy in [False, True]:
+            with self.subTest(lazy=lazy):
+                handle = self.archive.open(
+                    "a.txt", expected_size=len(DATA), lazy=lazy
+                )
+                with handle:
+                    self.assertEqual(handle.read(), DATA)
+
+    def test_mismatched_size_raises(self) -> None:
+        with self.assertRaises(nd_zipfile.BadZipFile):
+            self.archive.open("a.txt", expected_size=len(DATA) + 1)
+
+    def test_mismatched_size_raises_on_lazy_open(self) -> None:
+        with self.assertRaises(nd_zipfile.BadZipFile):
+            self.archive.open("a.txt", expected_size=len(DATA) - 1, lazy=True)
+
+        # Nothing was left locked by the failed open.
+        self.assertEqual(self.archive.read("a.txt"), DATA)
+
+
+if __name__ == "__main__":
+    unittest.main()
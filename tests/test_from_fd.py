@@ -0,0 +1,88 @@
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {"a.txt": b"alpha", "dir/b.txt": b"beta"}
+
+
+@unittest.skipUnless(os.name == "posix", "from_fd takes a raw handle on Windows")
+class FromFdTests(unittest.TestCase):
+    def setUp(self) -> None:
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.path = os.path.join(directory.name, "archive.zip")
+
+    def test_reads_members_from_a_regular_file(self) -> None:
+        with open(self.path, "wb") as file:
+            file.write(make_archive(ENTRIES).getvalue())
+
+        fd = os.open(self.path, os.O_RDONLY)
+        with nd_zipfile.ZipFile.from_fd(fd) as archive:
+            self.assertEqual(archive.namelist(), list(ENTRIES))
+            for name, data in ENTRIES.items():
+                self.assertEqual(archive.read(name), data)
+
+        # The archive owns the descriptor, so closing it closes the descriptor too.
+        with self.assertRaises(OSError):
+            os.fstat(fd)
+
+    def test_writes_to_a_regular_file(self) -> None:
+        fd = os.open(self.path, os.O_RDWR | os.O_CREAT | os.O_TRUNC)
+        with nd_zipfile.ZipFile.from_fd(fd, "w") as archive:
+            for name, data in ENTRIES.items():
+                archive.writestr(name, data)
+
+        with zipfile.ZipFile(self.path) as archive:
+            self.assertEqual(
+                {name: archive.read(name) for name in archive.namelist()}, ENTRIES
+            )
+
+    def test_write_limits_apply(self) -> None:
+        fd = os.open(self.path, os.O_RDWR | os.O_CREAT | os.O_TRUNC)
+        with nd_zipfile.ZipFile.from_fd(
+            fd, "w", allowZip64=False, max_entry_size=4
+        ) as archive:
+            archive.writestr("small.txt", b"tiny")
+            with self.assertRaises(ValueError):
+                archive.writestr("large.txt", b"too large")
+            with self.assertRaises(nd_zipfile.LargeZipFile):
+                archive.open("zip64.txt", "w", force_zip64=True)
+
+        with zipfile.ZipFile(self.path) as archive:
+            self.assertEqual(archive.namelist(), ["small.txt"])
+
+    def test_require_plaintext_applies(self) -> None:
+        with open(self.path, "wb") as file:
+            file.write(make_archive(ENTRIES).getvalue())
+
+        fd = os.open(self.path, os.O_RDONLY)
+        with nd_zipfile.ZipFile.from_fd(fd, require_plaintext=True) as archive:
+            self.assertEqual(archive.read("a.txt"), ENTRIES["a.txt"])
+
+    def test_limits_for_the_wrong_mode_are_rejected(self) -> None:
+        fd = os.open(self.path, os.O_RDWR | os.O_CREAT | os.O_TRUNC)
+        self.addCleanup(os.close, fd)
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile.from_fd(fd, max_entry_size=4)
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile.from_fd(fd, "w", require_plaintext=True)
+
+    def test_pipe_is_rejected_as_unseekable(self) -> None:
+        read_fd, write_fd = os.pipe()
+        os.write(write_fd, make_archive(ENTRIES).getvalue())
+        os.close(write_fd)
+
+        with self.assertRaises(OSError):
+            nd_zipfile.ZipFile.from_fd(read_fd)
+
+    def test_negative_fd_is_rejected(self) -> None:
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile.from_fd(-1)
+
+
+if __name__ == "__main__":
+    unittest.main()
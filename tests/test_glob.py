@@ -0,0 +1,47 @@
+import unittest
+
+import nd_zipfile
+
+from tests.helpers import make_archive
+
+NESTED = {
+    "top.png": b"1",
+    "readme.txt": b"2",
+    "a/": b"",
+    "a/b.png": b"3",
+    "a/c/d.png": b"4",
+    "a/c/e.jpg": b"5",
+    "a1.txt": b"6",
+    "ab.txt": b"7",
+}
+
+
+class GlobTest(unittest.TestCase):
+    def setUp(self) -> None:
+        self.archive = nd_zipfile.ZipFile(make_archive(NESTED))
+
+    def tearDown(self) -> None:
+        self.archive.close()
+
+    def test_star_stays_within_a_component(self) -> None:
+        self.assertEqual(self.archive.glob("*.png"), ["top.png"])
+        self.assertEqual(self.archive.glob("a/*.png"), ["a/b.png"])
+        self.assertEqual(self.archive.glob("a/*/*"), ["a/c/d.png", "a/c/e.jpg"])
+
+    def test_question_mark_matches_one_character(self) -> None:
+        self.assertEqual(self.archive.glob("a?.txt"), ["a1.txt", "ab.txt"])
+        self.assertEqual(self.archive.glob("?.txt"), [])
+
+    def test_double_star_matches_any_depth(self) -> None:
+        self.assertEqual(
+            self.archive.glob("**/*.png"),
+            ["top.png", "a/b.png", "a/c/d.png"],
+        )
+        self.assertEqual(self.archive.glob("a/**/e.jpg"), ["a/c/e.jpg"])
+
+    def test_results_are_in_central_directory_order(self) -> None:
+        self.assertEqual(self.archive.glob("**"), self.archive.namelist())
+
+
+if __name__ == "__main__":
+    unittest.main()
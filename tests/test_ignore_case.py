@@ -0,0 +1,31 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+
+class IgnoreCaseTests(unittest.TestCase):
+    def test_open_matches_a_differently_cased_name(self) -> None:
+        buffer = make_archive({"README.txt": b"read me"})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with archive.open("readme.txt", ignore_case=True) as handle:
+                self.assertEqual(handle.read(), b"read me")
+
+            with self.assertRaises(KeyError):
+                archive.open("readme.txt")
+
+    def test_exact_match_is_preferred(self) -> None:
+        buffer = make_archive({"README.txt": b"upper", "readme.txt": b"lower"})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with archive.open("readme.txt", ignore_case=True) as handle:
+                self.assertEqual(handle.read(), b"lower")
+
+    def test_ambiguous_match_raises(self) -> None:
+        buffer = make_archive({"README.txt": b"upper", "readme.txt": b"lower"})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with self.assertRaises(ValueError):
+                archive.open("ReadMe.txt", ignore_case=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,66 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+FIELDS = [
+    "filename",
+    "compress_type",
+    "compress_size",
+    "file_size",
+    "CRC",
+    "date_time",
+    "header_offset",
+]
+
+
+def make_mixed_archive() -> io.BytesIO:
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        archive.writestr(
+            zipfile.ZipInfo("stored.txt", (2001, 2, 3, 4, 5, 6)), b"stored" * 10
+        )
+        archive.writestr(
+            zipfile.ZipInfo("deflated.txt", (2020, 12, 31, 23, 59, 58)),
+            b"deflated" * 100,
+            zipfile.ZIP_DEFLATED,
+        )
+        archive.writestr("dir/", b"")
+        archive.writestr("dir/bzip2.txt", b"bzip2" * 100, zipfile.ZIP_BZIP2)
+    buffer.seek(0)
+    return buffer
+
+
+class InfolistTests(unittest.TestCase):
+    def test_fields_match_zipfile(self) -> None:
+        buffer = make_mixed_archive()
+        with zipfile.ZipFile(buffer) as archive:
+            expected = [
+                {field: getattr(info, field) for field in FIELDS}
+                for info in archive.infolist()
+            ]
+
+        buffer.seek(0)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            actual = [
+                {field: getattr(info, field) for field in FIELDS}
+                for info in archive.infolist()
+            ]
+
+        self.assertEqual(actual, expected)
+
+    def test_order_matches_namelist(self) -> None:
+        with nd_zipfile.ZipFile(make_mixed_archive(), "r") as archive:
+            names = [info.filename for info in archive.infolist()]
+            self.assertEqual(names, archive.namelist())
+
+    def test_empty_archive(self) -> None:
+        buffer = io.BytesIO()
+        zipfile.ZipFile(buffer, "w").close()
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(archive.infolist(), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
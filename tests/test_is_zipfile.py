@@ -0,0 +1,65 @@
+from __future__ import annotations
+
+import io
+import os
+import tempfile
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+JUNK = b"not a zip archive" * 100
+
+
+class FailingFile(io.BytesIO):
+    def __init__(self, data: bytes, failing_method: str) -> None:
+        super().__init__(data)
+        self.failing_method = failing_method
+
+    def read(self, size: int | None = -1) -> bytes:
+        if self.failing_method == "read":
+            raise OSError("read failed")
+        return super().read(size)
+
+    def seek(self, offset: int, whence: int = io.SEEK_SET) -> int:
+        if self.failing_method == "seek":
+            raise RuntimeError("seek failed")
+        return super().seek(offset, whence)
+
+
+class IsZipfileTests(unittest.TestCase):
+    def test_paths(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            archive_path = os.path.join(directory, "archive.zip")
+            with open(archive_path, "wb") as file:
+                file.write(make_archive({"a.txt": b"alpha"}).getvalue())
+            junk_path = os.path.join(directory, "junk.bin")
+            with open(junk_path, "wb") as file:
+                file.write(JUNK)
+
+            self.assertTrue(nd_zipfile.is_zipfile(archive_path))
+            self.assertFalse(nd_zipfile.is_zipfile(junk_path))
+            self.assertFalse(nd_zipfile.is_zipfile(os.path.join(directory, "missing")))
+            self.assertFalse(nd_zipfile.is_zipfile(directory))
+
+    def test_file_likes(self) -> None:
+        self.assertTrue(nd_zipfile.is_zipfile(make_archive({"a.txt": b"alpha"})))
+        self.assertFalse(nd_zipfile.is_zipfile(io.BytesIO(JUNK)))
+        self.assertFalse(nd_zipfile.is_zipfile(io.BytesIO()))
+
+    def test_failing_file_likes(self) -> None:
+        data = make_archive({"a.txt": b"alpha"}).getvalue()
+        for failing_method in ["read", "seek"]:
+            with self.subTest(failing_method=failing_method):
+                self.assertFalse(
+                    nd_zipfile.is_zipfile(FailingFile(data, failing_method))
+                )
+
+    def test_non_path_objects(self) -> None:
+        for value in [None, 42, object(), [b"PK"]]:
+            with self.subTest(value=value):
+                self.assertFalse(nd_zipfile.is_zipfile(value))
+
+
+if __name__ == "__main__":
+    unittest.main()
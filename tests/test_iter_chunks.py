@@ -0,0 +1,63 @@
+from __future__ import annotations
+
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+DATA = bytes(range(256)) * 1000
+
+
+class AbortRead(Exception):
+    pass
+
+
+class IterChunksTests(unittest.TestCase):
+    def test_callback_reports_the_member_size(self) -> None:
+        buffer = make_archive({"data.bin": DATA}, zipfile.ZIP_DEFLATED)
+        positions: list[int] = []
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            chunks = archive.iter_chunks(
+                "data.bin", chunk_size=4096, callback=positions.append
+            )
+            self.assertEqual(b"".join(chunks), DATA)
+
+        self.assertGreater(len(positions), 1)
+        self.assertEqual(positions, sorted(positions))
+        self.assertEqual(positions[-1], len(DATA))
+
+    def test_raising_callback_aborts_the_read(self) -> None:
+        buffer = make_archive({"data.bin": DATA})
+
+        def callback(position: int) -> None:
+            if position > 8192:
+                raise AbortRead
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            read = 0
+            with self.assertRaises(AbortRead):
+                for chunk in archive.iter_chunks(
+                    "data.bin", chunk_size=4096, callback=callback
+                ):
+                    read += len(chunk)
+            self.assertEqual(read, 8192)
+
+            # The aborted read released the archive.
+            self.assertEqual(archive.read("data.bin"), DATA)
+
+    def test_accepts_zip_info(self) -> None:
+        buffer = make_archive({"a.txt": b"alpha\r\nbeta"})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            info = archive.getinfo("a.txt")
+            self.assertEqual(b"".join(archive.iter_chunks(info)), b"alpha\r\nbeta")
+            self.assertEqual(archive.read_text(info), "alpha\nbeta")
+            self.assertEqual(archive.read_into_array(info, 1), b"alpha\r\nbeta")
+
+            buf = bytearray()
+            self.assertEqual(archive.read_into_buf(info, buf), 11)
+            self.assertEqual(buf, b"alpha\r\nbeta")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,46 @@
+import tempfile
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {"a.txt": b"alpha" * 100, "b.txt": b"beta" * 100}
+
+
+class LazyOpenTests(unittest.TestCase):
+    def test_other_operations_proceed_before_first_read(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            handle = archive.open("a.txt", lazy=True)
+
+            # The archive isn't locked yet, so everything else still works.
+            self.assertEqual(archive.read("b.txt"), ENTRIES["b.txt"])
+            self.assertIsNone(archive.testzip())
+            with tempfile.TemporaryDirectory() as directory:
+                archive.extract("b.txt", directory)
+            with archive.open("b.txt") as other:
+                self.assertEqual(other.read(), ENTRIES["b.txt"])
+
+            with handle:
+                self.assertEqual(handle.read(5), b"alpha")
+                # Once read, the handle holds the lock like an eager one.
+                with self.assertRaises(RuntimeError):
+                    archive.read("b.txt")
+                self.assertEqual(handle.read(), ENTRIES["a.txt"][5:])
+
+            # Closing the handle releases the lock again.
+            self.assertEqual(archive.read("b.txt"), ENTRIES["b.txt"])
+
+    def test_eager_open_locks_immediately(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            with archive.open("a.txt"):
+                with self.assertRaises(RuntimeError):
+                    archive.read("b.txt")
+
+    def test_closing_an_unread_lazy_handle(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            archive.open("a.txt", lazy=True).close()
+            self.assertEqual(archive.read("a.txt"), ENTRIES["a.txt"])
+
+
+if __name__ == "__main__":
+    unittest.main()
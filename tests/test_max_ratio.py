@@ -0,0 +1,50 @@
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+# Zeros deflate to roughly a thousandth of their size.
+BOMB = bytes(10 * 1024 * 1024)
+TEXT = b"Not very compressible, as it is short."
+
+
+class MaxRatioTests(unittest.TestCase):
+    def setUp(self) -> None:
+        buffer = make_archive({"bomb.bin": BOMB, "text.txt": TEXT}, zipfile.ZIP_DEFLATED)
+        self.archive = nd_zipfile.ZipFile(buffer, "r")
+        self.addCleanup(self.archive.close)
+
+    def test_read_aborts_partway(self) -> None:
+        with self.archive.open("bomb.bin", max_ratio=100) as handle:
+            read = 0
+            with self.assertRaises(nd_zipfile.ZipBombError):
+                while handle.read(64 * 1024):
+                    read += 64 * 1024
+            self.assertLess(read, len(BOMB))
+
+    def test_iter_chunks_aborts_partway(self) -> None:
+        read = 0
+        with self.assertRaises(nd_zipfile.ZipBombError):
+            for chunk in self.archive.iter_chunks("bomb.bin", max_ratio=100):
+                read += len(chunk)
+        self.assertLess(read, len(BOMB))
+
+        # The aborted read released the archive.
+        self.assertEqual(self.archive.read("text.txt"), TEXT)
+
+    def test_members_within_the_limit_are_read(self) -> None:
+        with self.archive.open("text.txt", max_ratio=100) as handle:
+            self.assertEqual(handle.read(), TEXT)
+        with self.archive.open("bomb.bin", max_ratio=10000) as handle:
+            self.assertEqual(len(handle.read()), len(BOMB))
+
+    def test_invalid_limit(self) -> None:
+        for max_ratio in [0.0, -1.0, float("nan")]:
+            with self.subTest(max_ratio=max_ratio):
+                with self.assertRaises(ValueError):
+                    self.archive.open("text.txt", max_ratio=max_ratio)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,49 @@
+import io
+import json
+import unittest
+import zipfile
+
+import nd_zipfile
+
+
+class MetadataJsonTests(unittest.TestCase):
+    def test_one_object_per_member(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr(zipfile.ZipInfo("a.txt", (2020, 1, 2, 3, 4, 6)), b"alpha")
+            archive.writestr(
+                zipfile.ZipInfo("dir/b.txt", (2021, 5, 6, 7, 8, 10)),
+                b"beta" * 100,
+                zipfile.ZIP_DEFLATED,
+            )
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            manifest = json.loads(archive.metadata_json())
+
+        with zipfile.ZipFile(buffer) as archive:
+            expected = [
+                {
+                    "filename": info.filename,
+                    "compress_size": info.compress_size,
+                    "file_size": info.file_size,
+                    "CRC": info.CRC,
+                    "compress_type": info.compress_type,
+                    "date_time": list(info.date_time),
+                }
+                for info in archive.infolist()
+            ]
+
+        self.assertIsInstance(manifest, list)
+        self.assertEqual(len(manifest), 2)
+        self.assertEqual(manifest, expected)
+
+    def test_empty_archive(self) -> None:
+        buffer = io.BytesIO()
+        zipfile.ZipFile(buffer, "w").close()
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(json.loads(archive.metadata_json()), [])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,34 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+
+class MethodHistogramTests(unittest.TestCase):
+    def test_counts_mixed_methods(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("a", b"a" * 100, zipfile.ZIP_DEFLATED)
+            archive.writestr("b", b"b", zipfile.ZIP_STORED)
+            archive.writestr("c", b"c" * 100, zipfile.ZIP_DEFLATED)
+            archive.writestr("d/", b"", zipfile.ZIP_STORED)
+            archive.writestr("e", b"e" * 100, zipfile.ZIP_DEFLATED)
+
+        with nd_zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(
+                archive.method_histogram(),
+                {nd_zipfile.ZIP_STORED: 2, nd_zipfile.ZIP_DEFLATED: 3},
+            )
+
+    def test_empty_archive(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w"):
+            pass
+
+        with nd_zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.method_histogram(), {})
+
+
+if __name__ == "__main__":
+    unittest.main()
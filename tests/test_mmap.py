@@ -0,0 +1,57 @@
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {
+    "a.txt": b"alpha" * 1000,
+    "dir/": b"",
+    "dir/b.bin": bytes(range(256)) * 100,
+    "empty.txt": b"",
+}
+
+
+class MmapTests(unittest.TestCase):
+    def test_mapped_reads_match_unmapped_reads(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "archive.zip")
+            with open(path, "wb") as file:
+                file.write(make_archive(ENTRIES, zipfile.ZIP_DEFLATED).getvalue())
+
+            contents = {}
+            for mmap in [False, True]:
+                with nd_zipfile.ZipFile(path, "r", mmap=mmap) as archive:
+                    self.assertIsNone(archive.testzip())
+                    contents[mmap] = {
+                        name: archive.read(name) for name in archive.namelist()
+                    }
+                    with archive.open("dir/b.bin") as handle:
+                        handle.seek(1000)
+                        contents[mmap]["seeked"] = handle.read(100)
+
+        self.assertEqual(contents[True], contents[False])
+        self.assertEqual(contents[True]["a.txt"], ENTRIES["a.txt"])
+        self.assertEqual(contents[True]["seeked"], ENTRIES["dir/b.bin"][1000:1100])
+
+    def test_empty_file_falls_back(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "empty.zip")
+            open(path, "wb").close()
+
+            # An empty file can't be mapped, so it is read normally and fails the same way.
+            for mmap in [False, True]:
+                with self.subTest(mmap=mmap):
+                    with self.assertRaises(nd_zipfile.BadZipFile):
+                        nd_zipfile.ZipFile(path, "r", mmap=mmap)
+
+    def test_file_likes_are_rejected(self) -> None:
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile(io.BytesIO(make_archive(ENTRIES).getvalue()), mmap=True)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,71 @@
+import datetime
+import io
+import time
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import NEW_YORK, local_timezone
+
+# Spread over the year, so some are in daylight saving time and some aren't.
+DATE_TIMES = {
+    "winter.txt": (2020, 1, 1, 0, 0, 0),
+    "spring.txt": (2020, 4, 15, 12, 30, 0),
+    "summer.txt": (2020, 7, 4, 18, 0, 0),
+    "autumn.txt": (2020, 11, 20, 6, 15, 0),
+}
+
+
+def make_dated_archive() -> io.BytesIO:
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        for name, date_time in DATE_TIMES.items():
+            archive.writestr(zipfile.ZipInfo(name, date_time), b"data")
+
+    buffer.seek(0)
+    return buffer
+
+
+@unittest.skipUnless(hasattr(time, "tzset"), "changing the time zone needs time.tzset")
+class ModifiedSinceTests(unittest.TestCase):
+    def test_unix_timestamps(self) -> None:
+        # Midnight on 2020-01-01 in New York, which is 05:00 in UTC.
+        winter = 1577854800
+        with local_timezone(NEW_YORK):
+            with nd_zipfile.ZipFile(make_dated_archive(), "r") as archive:
+                self.assertEqual(archive.modified_since(winter - 10), list(DATE_TIMES))
+                # Taken as UTC, winter.txt would be five hours older, and left out here.
+                self.assertEqual(
+                    archive.modified_since(winter - 3600), list(DATE_TIMES)
+                )
+                self.assertEqual(
+                    archive.modified_since(winter + 10),
+                    ["spring.txt", "summer.txt", "autumn.txt"],
+                )
+
+    def test_aware_datetimes(self) -> None:
+        # 2020-07-04 18:00 in New York is 22:00 in UTC.
+        after_summer = datetime.datetime(
+            2020, 7, 4, 22, 0, 10, tzinfo=datetime.timezone.utc
+        )
+        with local_timezone(NEW_YORK):
+            with nd_zipfile.ZipFile(make_dated_archive(), "r") as archive:
+                self.assertEqual(archive.modified_since(after_summer), ["autumn.txt"])
+
+    def test_naive_datetimes_are_local(self) -> None:
+        with local_timezone(NEW_YORK):
+            with nd_zipfile.ZipFile(make_dated_archive(), "r") as archive:
+                for name, date_time in DATE_TIMES.items():
+                    with self.subTest(name=name):
+                        just_after = datetime.datetime(*date_time)
+                        just_after += datetime.timedelta(seconds=10)
+                        expected = [
+                            other
+                            for other, other_date_time in DATE_TIMES.items()
+                            if other_date_time > date_time
+                        ]
+                        self.assertEqual(archive.modified_since(just_after), expected)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,44 @@
+import datetime
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+
+class MtimeDatetimeTests(unittest.TestCase):
+    def test_matches_date_time(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr(zipfile.ZipInfo("a.txt", (2021, 3, 4, 5, 6, 8)), b"a")
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            info = archive.getinfo("a.txt")
+            mtime = info.mtime_datetime()
+
+        self.assertIsInstance(mtime, datetime.datetime)
+        self.assertEqual(
+            (mtime.year, mtime.month, mtime.day, mtime.hour, mtime.minute, mtime.second),
+            info.date_time,
+        )
+        self.assertEqual(mtime, datetime.datetime(2021, 3, 4, 5, 6, 8))
+        self.assertIsNone(mtime.tzinfo)
+
+    def test_default_date_time(self) -> None:
+        info = nd_zipfile.ZipInfo("a.txt")
+        self.assertEqual(info.date_time, (1980, 1, 1, 0, 0, 0))
+        self.assertEqual(info.mtime_datetime(), datetime.datetime(1980, 1, 1))
+
+    def test_odd_seconds_round_down(self) -> None:
+        info = nd_zipfile.ZipInfo("a.txt")
+        info.date_time = (2021, 3, 4, 5, 6, 7)
+        self.assertEqual(info.mtime_datetime(), datetime.datetime(2021, 3, 4, 5, 6, 6))
+
+    def test_zero_month_and_day(self) -> None:
+        info = nd_zipfile.ZipInfo("a.txt")
+        info.date_time = (1980, 0, 0, 0, 0, 0)
+        self.assertEqual(info.mtime_datetime(), datetime.datetime(1980, 1, 1))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,69 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+BAD_CRC = 0x12345678
+
+
+class OverrideCrcTests(unittest.TestCase):
+    def write_archive(self, compression: int) -> io.BytesIO:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", compression) as archive:
+            archive.writestr("before.txt", b"before" * 100)
+            archive.writestr("bad.txt", b"corrupt" * 100, override_crc=BAD_CRC)
+            archive.writestr("after.txt", b"after" * 100)
+            self.assertEqual(archive.getinfo("bad.txt").CRC, BAD_CRC)
+
+        return buffer
+
+    def test_testzip_flags_the_bad_member(self) -> None:
+        for compression in [nd_zipfile.ZIP_STORED, nd_zipfile.ZIP_DEFLATED]:
+            with self.subTest(compression=compression):
+                buffer = self.write_archive(compression)
+
+                with nd_zipfile.ZipFile(buffer, "r") as archive:
+                    self.assertEqual(archive.testzip(), "bad.txt")
+                    self.assertEqual(archive.read("after.txt"), b"after" * 100)
+
+                with zipfile.ZipFile(buffer) as archive:
+                    self.assertEqual(archive.testzip(), "bad.txt")
+                    info = archive.getinfo("bad.txt")
+                    self.assertEqual(info.CRC, BAD_CRC)
+                    self.assertEqual(info.compress_type, compression)
+                    with self.assertRaises(zipfile.BadZipFile):
+                        archive.read("bad.txt")
+
+    def test_local_header_has_the_bad_crc(self) -> None:
+        buffer = self.write_archive(nd_zipfile.ZIP_STORED)
+        with zipfile.ZipFile(buffer) as archive:
+            offset = archive.getinfo("bad.txt").header_offset
+
+        header = buffer.getvalue()[offset : offset + 30]
+        self.assertEqual(header[:4], b"PK\x03\x04")
+        self.assertEqual(int.from_bytes(header[14:18], "little"), BAD_CRC)
+
+    def test_offsets_match_the_stdlib(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.writestr("before.txt", b"before" * 100)
+            archive.writestr("bad.txt", b"corrupt" * 100, override_crc=BAD_CRC)
+            archive.writestr("after.txt", b"after" * 100)
+            written = [
+                (info.filename, info.header_offset, info.compress_size)
+                for info in archive.infolist()
+            ]
+
+        with zipfile.ZipFile(buffer) as archive:
+            expected = [
+                (info.filename, info.header_offset, info.compress_size)
+                for info in archive.infolist()
+            ]
+        # The sizes of the last member are only known once it is finished, on close.
+        self.assertEqual(written[:2], expected[:2])
+        self.assertEqual(written[2][:2], expected[2][:2])
+
+
+if __name__ == "__main__":
+    unittest.main()
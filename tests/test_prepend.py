@@ -0,0 +1,66 @@
+from __future__ import annotations
+
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+
+STUB = b"#!/bin/sh\necho 'a self-extractor stub'\nexit 0\n" * 20
+ENTRIES = {"a.txt": b"alpha" * 100, "dir/b.bin": bytes(range(256))}
+
+
+def write_entries(archive: nd_zipfile.ZipFile) -> None:
+    for name, data in ENTRIES.items():
+        archive.writestr(name, data)
+
+
+class PrependTests(unittest.TestCase):
+    def check_readable(self, file: str | io.BytesIO, data: bytes) -> None:
+        self.assertTrue(data.startswith(STUB))
+
+        with zipfile.ZipFile(file) as archive:
+            self.assertIsNone(archive.testzip())
+            self.assertEqual({name: archive.read(name) for name in ENTRIES}, ENTRIES)
+            # Offsets are from the start of the file, so they include the stub.
+            for info in archive.infolist():
+                self.assertGreaterEqual(info.header_offset, len(STUB))
+
+        with nd_zipfile.ZipFile(file, "r") as archive:
+            self.assertEqual(archive.namelist(), list(ENTRIES))
+            self.assertEqual({name: archive.read(name) for name in ENTRIES}, ENTRIES)
+
+    def test_path(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "archive.zip")
+            archive = nd_zipfile.ZipFile(
+                path, "w", nd_zipfile.ZIP_DEFLATED, prepend=STUB
+            )
+            with archive:
+                write_entries(archive)
+
+            with open(path, "rb") as file:
+                data = file.read()
+            self.check_readable(path, data)
+
+    def test_file_like(self) -> None:
+        buffer = io.BytesIO()
+        archive = nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED, prepend=STUB)
+        with archive:
+            write_entries(archive)
+
+        self.check_readable(buffer, buffer.getvalue())
+
+    def test_only_for_new_archives(self) -> None:
+        buffer = io.BytesIO()
+        zipfile.ZipFile(buffer, "w").close()
+        for mode in ["r", "a"]:
+            with self.subTest(mode=mode):
+                with self.assertRaises(ValueError):
+                    nd_zipfile.ZipFile(buffer, mode, prepend=STUB)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,65 @@
+import io
+import os
+import struct
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+
+UID = 4321
+GID = 8765
+
+
+def make_owned_archive() -> io.BytesIO:
+    """Build an archive whose members carry an Info-ZIP Unix extra field."""
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        for name, data in [("owned/", b""), ("owned/file.txt", b"hello")]:
+            info = zipfile.ZipInfo(name)
+            info.extra = struct.pack("<HHBBIBI", 0x7875, 11, 1, 4, UID, 4, GID)
+            archive.writestr(info, data)
+        archive.writestr("unowned.txt", b"world")
+    buffer.seek(0)
+    return buffer
+
+
+@unittest.skipUnless(os.name == "posix", "ownership is only restored on Unix")
+class PreserveOwnershipTests(unittest.TestCase):
+    def test_extract_restores_owner(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            with nd_zipfile.ZipFile(make_owned_archive(), "r") as archive:
+                path = archive.extract(
+                    "owned/file.txt", directory, preserve_ownership=True
+                )
+
+            stat = os.stat(path)
+            if os.geteuid() == 0:
+                self.assertEqual((stat.st_uid, stat.st_gid), (UID, GID))
+            else:
+                self.assertEqual(stat.st_uid, os.geteuid())
+
+    def test_extractall_restores_owner(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            with nd_zipfile.ZipFile(make_owned_archive(), "r") as archive:
+                archive.extractall(directory, preserve_ownership=True)
+
+            if os.geteuid() == 0:
+                for name in ["owned", "owned/file.txt"]:
+                    stat = os.stat(os.path.join(directory, name))
+                    self.assertEqual((stat.st_uid, stat.st_gid), (UID, GID))
+
+            stat = os.stat(os.path.join(directory, "unowned.txt"))
+            self.assertEqual(stat.st_uid, os.geteuid())
+
+    def test_owner_is_ignored_by_default(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            with nd_zipfile.ZipFile(make_owned_archive(), "r") as archive:
+                archive.extractall(directory)
+
+            stat = os.stat(os.path.join(directory, "owned/file.txt"))
+            self.assertEqual(stat.st_uid, os.geteuid())
+
+
+if __name__ == "__main__":
+    unittest.main()
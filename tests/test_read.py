@@ -0,0 +1,39 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {"a.txt": b"alpha", "b.txt": b"beta"}
+
+
+class ReadTests(unittest.TestCase):
+    def setUp(self) -> None:
+        self.archive = nd_zipfile.ZipFile(make_archive(ENTRIES), "r")
+        self.addCleanup(self.archive.close)
+
+    def test_reads_whole_members(self) -> None:
+        for name, data in ENTRIES.items():
+            self.assertEqual(self.archive.read(name), data)
+
+    def test_reads_by_zip_info(self) -> None:
+        info = self.archive.getinfo("b.txt")
+        self.assertEqual(self.archive.read(info), ENTRIES["b.txt"])
+
+    def test_missing_member_raises_key_error(self) -> None:
+        with self.assertRaises(KeyError):
+            self.archive.read("nope")
+        for lazy in [False, True]:
+            with self.subTest(lazy=lazy):
+                with self.assertRaises(KeyError):
+                    self.archive.open("nope", lazy=lazy)
+
+        # The failed lookups released the archive.
+        self.assertEqual(self.archive.read("a.txt"), ENTRIES["a.txt"])
+
+    def test_repeated_reads_release_the_archive(self) -> None:
+        for _ in range(3):
+            self.assertEqual(self.archive.read("a.txt"), ENTRIES["a.txt"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,35 @@
+import array
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ITEMS = array.array("i", range(100))
+
+
+class ReadIntoArrayTests(unittest.TestCase):
+    def test_well_sized_member(self) -> None:
+        buffer = make_archive({"items.bin": ITEMS.tobytes()})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            data = archive.read_into_array("items.bin", ITEMS.itemsize)
+
+        self.assertEqual(array.array("i", data), ITEMS)
+
+    def test_mis_sized_member(self) -> None:
+        buffer = make_archive({"items.bin": ITEMS.tobytes()[:-1]})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with self.assertRaisesRegex(ValueError, "items.bin"):
+                archive.read_into_array("items.bin", ITEMS.itemsize)
+
+            # The archive is still usable after the failed read.
+            self.assertEqual(len(archive.read_into_array("items.bin", 1)), 399)
+
+    def test_zero_itemsize(self) -> None:
+        buffer = make_archive({"items.bin": ITEMS.tobytes()})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with self.assertRaises(ValueError):
+                archive.read_into_array("items.bin", 0)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,31 @@
+import json
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+DOCUMENT = {"name": "café", "items": [1, 2.5, None, True], "nested": {"empty": []}}
+
+
+class ReadJsonTests(unittest.TestCase):
+    def test_valid_json(self) -> None:
+        buffer = make_archive({"data.json": json.dumps(DOCUMENT).encode("utf-8")})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertEqual(archive.read_json("data.json"), DOCUMENT)
+
+    def test_invalid_json(self) -> None:
+        buffer = make_archive({"broken.json": b'{"name": '})
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with self.assertRaisesRegex(ValueError, "broken.json") as context:
+                archive.read_json("broken.json")
+
+        self.assertIsInstance(context.exception.__cause__, json.JSONDecodeError)
+
+    def test_missing_member(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({"a.txt": b"a"}), "r") as archive:
+            with self.assertRaises(KeyError):
+                archive.read_json("missing.json")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,49 @@
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {
+    "small.txt": b"s" * 10,
+    "large.txt": b"l" * 1000,
+    "dir/": b"",
+    "dir/medium.bin": bytes(range(200)),
+    "dir/tiny.bin": b"t",
+}
+
+
+class ReadMatchingTests(unittest.TestCase):
+    def test_size_threshold(self) -> None:
+        buffer = make_archive(ENTRIES, zipfile.ZIP_DEFLATED)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            contents = archive.read_matching(lambda info: info.file_size >= 100)
+
+        self.assertEqual(
+            contents,
+            {
+                "large.txt": ENTRIES["large.txt"],
+                "dir/medium.bin": ENTRIES["dir/medium.bin"],
+            },
+        )
+        # Matches come in central directory order.
+        self.assertEqual(list(contents), ["large.txt", "dir/medium.bin"])
+
+    def test_no_matches(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            self.assertEqual(archive.read_matching(lambda info: False), {})
+
+    def test_predicate_errors_propagate(self) -> None:
+        def predicate(info: nd_zipfile.ZipInfo) -> bool:
+            raise RuntimeError(info.filename)
+
+        with nd_zipfile.ZipFile(make_archive(ENTRIES), "r") as archive:
+            with self.assertRaisesRegex(RuntimeError, "small.txt"):
+                archive.read_matching(predicate)
+
+            # The archive is unlocked again afterwards.
+            self.assertEqual(archive.read("dir/tiny.bin"), b"t")
+
+
+if __name__ == "__main__":
+    unittest.main()
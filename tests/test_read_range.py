@@ -0,0 +1,41 @@
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+DATA = bytes(range(256)) * 400
+
+
+class ReadRangeTests(unittest.TestCase):
+    def check_slices(self, compression: int) -> None:
+        buffer = make_archive({"data.bin": DATA}, compression)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            data = archive.read("data.bin")
+            for start, length in [(0, 10), (1000, 5000), (50000, 100), (0, len(DATA))]:
+                self.assertEqual(
+                    archive.read_range("data.bin", start, length),
+                    data[start : start + length],
+                )
+
+            # Ranges running past the end are cut short.
+            self.assertEqual(
+                archive.read_range("data.bin", len(DATA) - 10, 100), DATA[-10:]
+            )
+            self.assertEqual(archive.read_range("data.bin", len(DATA) + 10, 100), b"")
+
+            # A default password doesn't matter for members without encryption.
+            archive.setpassword(b"secret")
+            self.assertEqual(
+                archive.read_range("data.bin", 1000, 5000), DATA[1000:6000]
+            )
+
+    def test_stored(self) -> None:
+        self.check_slices(zipfile.ZIP_STORED)
+
+    def test_deflated(self) -> None:
+        self.check_slices(zipfile.ZIP_DEFLATED)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,47 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+CONTENT = "first\r\nsecond\rthird\nfourth"
+
+
+class ReadTextTests(unittest.TestCase):
+    def setUp(self) -> None:
+        self.archive = nd_zipfile.ZipFile(
+            make_archive({"text.txt": CONTENT.encode("utf-8")}), "r"
+        )
+        self.addCleanup(self.archive.close)
+
+    def test_universal_newlines(self) -> None:
+        self.assertEqual(
+            self.archive.read_text("text.txt"), "first\nsecond\nthird\nfourth"
+        )
+
+    def test_untranslated_newlines(self) -> None:
+        self.assertEqual(self.archive.read_text("text.txt", newline=""), CONTENT)
+
+    def test_specific_newline(self) -> None:
+        # Like `open`, a specific newline leaves line endings untranslated when reading.
+        for newline in ["\n", "\r", "\r\n"]:
+            with self.subTest(newline=newline):
+                self.assertEqual(
+                    self.archive.read_text("text.txt", newline=newline), CONTENT
+                )
+
+    def test_illegal_newline(self) -> None:
+        with self.assertRaises(ValueError):
+            self.archive.read_text("text.txt", newline="\t")
+
+    def test_encoding(self) -> None:
+        archive = nd_zipfile.ZipFile(
+            make_archive({"latin.txt": "café\r\n".encode("latin-1")}), "r"
+        )
+        with archive:
+            self.assertEqual(
+                archive.read_text("latin.txt", encoding="latin-1"), "café\n"
+            )
+
+
+if __name__ == "__main__":
+    unittest.main()
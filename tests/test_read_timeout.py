@@ -0,0 +1,63 @@
+import io
+import threading
+import time
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {f"{index}.txt": f"member {index}".encode() for index in range(50)}
+
+
+class SlowFile(io.BytesIO):
+    """A file object whose reads take `delay` seconds once `slow` is set."""
+
+    def __init__(self, data: bytes, delay: float) -> None:
+        super().__init__(data)
+        self.delay = delay
+        self.slow = False
+
+    def read(self, size: int = -1) -> bytes:
+        if self.slow:
+            time.sleep(self.delay)
+        return super().read(size)
+
+
+class ReadTimeoutTests(unittest.TestCase):
+    def test_slow_read_raises_timeout(self) -> None:
+        file = SlowFile(make_archive(ENTRIES).getvalue(), delay=0.5)
+        archive = nd_zipfile.ZipFile(file, "r", read_timeout=0.05)
+        archive.prefetch()
+        file.slow = True
+
+        with self.assertRaises(TimeoutError):
+            archive.read("0.txt")
+
+        # The object's position is unknown now, so later reads fail fast.
+        started = time.monotonic()
+        with self.assertRaises(TimeoutError):
+            archive.read("1.txt")
+        self.assertLess(time.monotonic() - started, 0.5)
+
+    def test_fast_reads_succeed(self) -> None:
+        file = SlowFile(make_archive(ENTRIES).getvalue(), delay=0.0)
+        with nd_zipfile.ZipFile(file, "r", read_timeout=5.0) as archive:
+            for name, data in ENTRIES.items():
+                self.assertEqual(archive.read(name), data)
+
+    def test_reads_share_one_thread(self) -> None:
+        file = SlowFile(make_archive(ENTRIES).getvalue(), delay=0.0)
+        with nd_zipfile.ZipFile(file, "r", read_timeout=5.0) as archive:
+            archive.read("0.txt")
+            threads = threading.active_count()
+            for name in ENTRIES:
+                archive.read(name)
+            self.assertEqual(threading.active_count(), threads)
+
+    def test_read_timeout_must_be_positive(self) -> None:
+        with self.assertRaises(ValueError):
+            nd_zipfile.ZipFile(make_archive(ENTRIES), "r", read_timeout=0)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,40 @@
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+# Much larger than the read-ahead buffer, with no newline to stop at.
+LONG_LINE = bytes(range(ord("a"), ord("z") + 1)) * 200_000
+
+
+class ReadlineTests(unittest.TestCase):
+    def test_limit_without_newlines(self) -> None:
+        buffer = make_archive({"long.txt": LONG_LINE}, zipfile.ZIP_DEFLATED)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with archive.open("long.txt") as handle:
+                self.assertEqual(handle.readline(10), LONG_LINE[:10])
+                self.assertEqual(handle.readline(0), b"")
+                self.assertEqual(handle.readline(5), LONG_LINE[10:15])
+
+                # The whole member comes back in limited pieces, after which there is EOF.
+                pieces = [LONG_LINE[:15]]
+                while piece := handle.readline(4096):
+                    self.assertLessEqual(len(piece), 4096)
+                    pieces.append(piece)
+                self.assertEqual(b"".join(pieces), LONG_LINE)
+                self.assertEqual(handle.readline(10), b"")
+
+    def test_limit_stops_before_a_later_newline(self) -> None:
+        data = LONG_LINE + b"\nshort\n"
+        buffer = make_archive({"lines.txt": data}, zipfile.ZIP_DEFLATED)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with archive.open("lines.txt") as handle:
+                self.assertEqual(handle.readline(100), LONG_LINE[:100])
+                self.assertEqual(handle.readline(), LONG_LINE[100:] + b"\n")
+                self.assertEqual(handle.readline(100), b"short\n")
+                self.assertEqual(handle.readline(100), b"")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,136 @@
+import io
+import os
+import tempfile
+import time
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import NEW_YORK, local_timezone
+
+DATE_TIME = (2020, 1, 1, 0, 0, 0)
+# Midnight in New York, at UTC-5 in January.
+TIMESTAMP = 1577854800
+
+
+def make_timestamped_archive() -> io.BytesIO:
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w") as archive:
+        archive.writestr(zipfile.ZipInfo("a.txt", DATE_TIME), b"alpha")
+        archive.writestr(zipfile.ZipInfo("dir/b.txt", DATE_TIME), b"beta")
+
+    return buffer
+
+
+@unittest.skipUnless(hasattr(time, "tzset"), "changing the time zone needs time.tzset")
+class SkipUnchangedTests(unittest.TestCase):
+    def setUp(self) -> None:
+        directory = tempfile.TemporaryDirectory()
+        self.addCleanup(directory.cleanup)
+        self.directory = directory.name
+
+        timezone = local_timezone(NEW_YORK)
+        timezone.__enter__()
+        self.addCleanup(timezone.__exit__, None, None, None)
+
+        self.archive = nd_zipfile.ZipFile(make_timestamped_archive(), "r")
+        self.addCleanup(self.archive.close)
+
+    def path(self, name: str) -> str:
+        return os.path.join(self.directory, name)
+
+    def read(self, name: str) -> bytes:
+        with open(self.path(name), "rb") as file:
+            return file.read()
+
+    def overwrite(self, name: str, data: bytes) -> None:
+        """Overwrite an extracted file, keeping its modification time."""
+        mtime = os.stat(self.path(name)).st_mtime
+        with open(self.path(name), "wb") as file:
+            file.write(data)
+        os.utime(self.path(name), (mtime, mtime))
+
+    def test_modification_time_is_local(self) -> None:
+        self.archive.extractall(self.directory)
+
+        self.assertEqual(os.stat(self.path("a.txt")).st_mtime, TIMESTAMP)
+        self.assertEqual(os.stat(self.path("dir/b.txt")).st_mtime, TIMESTAMP)
+
+    def test_second_extraction_skips_unchanged_members(self) -> None:
+        self.archive.extractall(self.directory, skip_unchanged=True)
+        # Same size and time, so it looks unchanged even though its contents differ.
+        self.overwrite("a.txt", b"ALPHA")
+        # A different size marks the file as changed.
+        self.overwrite("dir/b.txt", b"changed")
+
+        self.archive.extractall(self.directory, skip_unchanged=True)
+
+        self.assertEqual(self.read("a.txt"), b"ALPHA")
+        self.assertEqual(self.read("dir/b.txt"), b"beta")
+
+    def test_changed_modification_time_is_extracted(self) -> None:
+        self.archive.extractall(self.directory)
+        with open(self.path("a.txt"), "wb") as file:
+            file.write(b"ALPHA")
+        os.utime(self.path("a.txt"), (TIMESTAMP + 60, TIMESTAMP + 60))
+
+        self.archive.extractall(self.directory, skip_unchanged=True)
+
+        self.assertEqual(self.read("a.txt"), b"alpha")
+
+    def test_files_from_other_tools_are_skipped(self) -> None:
+        # Tools like Info-ZIP's unzip set the member's time, taken as local time.
+        os.makedirs(self.path("dir"))
+        for name, data in [("a.txt", b"ALPHA"), ("dir/b.txt", b"BETA")]:
+            with open(self.path(name), "wb") as file:
+                file.write(data)
+            mtime = time.mktime(DATE_TIME + (0, 0, -1))
+            os.utime(self.path(name), (mtime, mtime))
+
+        self.archive.extractall(self.directory, skip_unchanged=True)
+
+        self.assertEqual(self.read("a.txt"), b"ALPHA")
+        self.assertEqual(self.read("dir/b.txt"), b"BETA")
+
+    def test_without_skip_unchanged_everything_is_extracted(self) -> None:
+        self.archive.extractall(self.directory)
+        self.overwrite("a.txt", b"ALPHA")
+
+        self.archive.extractall(self.directory)
+
+        self.assertEqual(self.read("a.txt"), b"alpha")
+
+
+class LocalWriteTimeTests(unittest.TestCase):
+    @unittest.skipUnless(hasattr(time, "tzset"), "changing the time zone needs time.tzset")
+    def test_written_file_times_are_local(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "a.txt")
+            with open(path, "wb") as file:
+                file.write(b"alpha")
+            os.utime(path, (TIMESTAMP, TIMESTAMP))
+
+            buffer = io.BytesIO()
+            with local_timezone(NEW_YORK):
+                with nd_zipfile.ZipFile(buffer, "w") as archive:
+                    archive.write(path, "a.txt")
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.getinfo("a.txt").date_time, DATE_TIME)
+
+    def test_current_time_is_local(self) -> None:
+        buffer = io.BytesIO()
+        before = time.localtime()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("a.txt", b"alpha")
+        after = time.localtime()
+
+        with zipfile.ZipFile(buffer) as archive:
+            date_time = archive.getinfo("a.txt").date_time
+        # Zip timestamps round seconds down to even numbers.
+        self.assertLessEqual(before[:5] + (before[5] - before[5] % 2,), date_time)
+        self.assertLessEqual(date_time, after[:6])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,43 @@
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {
+    "zebra.txt": b"z" * 100,
+    "b/nested.txt": b"nested",
+    "apple.txt": b"a" * 1000,
+    "b/": b"",
+    "Capital.txt": b"capital",
+}
+
+
+class SortedCopyTests(unittest.TestCase):
+    def test_copy_is_sorted_with_unchanged_contents(self) -> None:
+        source = make_archive(ENTRIES, zipfile.ZIP_DEFLATED)
+        with tempfile.TemporaryDirectory() as directory:
+            output_path = os.path.join(directory, "sorted.zip")
+            with nd_zipfile.ZipFile(source, "r") as archive:
+                archive.sorted_copy(output_path)
+
+            with zipfile.ZipFile(output_path) as archive:
+                self.assertIsNone(archive.testzip())
+                names = archive.namelist()
+                self.assertEqual(names, sorted(ENTRIES))
+                self.assertEqual({name: archive.read(name) for name in names}, ENTRIES)
+                copied = {info.filename: info for info in archive.infolist()}
+
+        # Members are copied raw, so they keep their compression.
+        with zipfile.ZipFile(source) as archive:
+            for info in archive.infolist():
+                copy = copied[info.filename]
+                self.assertEqual(copy.compress_type, info.compress_type)
+                self.assertEqual(copy.compress_size, info.compress_size)
+                self.assertEqual(copy.CRC, info.CRC)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,60 @@
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+
+
+class SplitTest(unittest.TestCase):
+    def setUp(self) -> None:
+        self.directory = tempfile.TemporaryDirectory()
+        self.path = os.path.join(self.directory.name, "archive.zip")
+        # Random data doesn't compress, so member sizes are predictable.
+        self.entries = {f"member{index}.bin": os.urandom(1000) for index in range(10)}
+        self.entries["large.bin"] = os.urandom(5000)
+        with zipfile.ZipFile(self.path, "w", zipfile.ZIP_DEFLATED) as archive:
+            for name, data in self.entries.items():
+                archive.writestr(name, data)
+
+    def tearDown(self) -> None:
+        self.directory.cleanup()
+
+    def test_union_of_volumes_reads_back(self) -> None:
+        with nd_zipfile.ZipFile(self.path) as archive:
+            paths = archive.split(3000)
+
+        self.assertGreater(len(paths), 1)
+        contents = {}
+        for path in paths:
+            with zipfile.ZipFile(path) as volume:
+                self.assertIsNone(volume.testzip())
+                for name in volume.namelist():
+                    self.assertNotIn(name, contents)
+                    contents[name] = volume.read(name)
+        self.assertEqual(contents, self.entries)
+
+    def test_volumes_stay_under_the_limit(self) -> None:
+        with nd_zipfile.ZipFile(self.path) as archive:
+            paths = archive.split(3000)
+
+        for path in paths:
+            with zipfile.ZipFile(path) as volume:
+                names = volume.namelist()
+            if names == ["large.bin"]:
+                continue
+            self.assertLessEqual(os.path.getsize(path), 3000)
+
+    def test_entries_over_the_limit_get_their_own_volume(self) -> None:
+        with nd_zipfile.ZipFile(self.path) as archive:
+            paths = archive.split(3000)
+
+        volumes = []
+        for path in paths:
+            with zipfile.ZipFile(path) as volume:
+                volumes.append(volume.namelist())
+        self.assertIn(["large.bin"], volumes)
+
+
+if __name__ == "__main__":
+    unittest.main()
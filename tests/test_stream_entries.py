@@ -0,0 +1,105 @@
+import io
+import unittest
+import zipfile
+import zlib
+
+import nd_zipfile
+from tests.helpers import RawMember, make_archive, make_raw_archive
+
+ENTRIES = {
+    "a.txt": b"alpha" * 100,
+    "dir/": b"",
+    "dir/b.bin": bytes(range(256)) * 50,
+    "c.txt": b"gamma",
+}
+PASSWORD = b"secret"
+
+
+def zip_crypto_encrypt(data: bytes, password: bytes) -> bytes:
+    """Encrypt stored data with traditional PKWARE encryption."""
+    keys = [0x12345678, 0x23456789, 0x34567890]
+
+    def crc32_byte(crc: int, byte: int) -> int:
+        # zlib inverts the CRC before and after, which the key updates don't.
+        return ~zlib.crc32(bytes([byte]), ~crc & 0xFFFFFFFF) & 0xFFFFFFFF
+
+    def update_keys(byte: int) -> None:
+        keys[0] = crc32_byte(keys[0], byte)
+        keys[1] = ((keys[1] + (keys[0] & 0xFF)) * 134775813 + 1) & 0xFFFFFFFF
+        keys[2] = crc32_byte(keys[2], keys[1] >> 24)
+
+    for byte in password:
+        update_keys(byte)
+
+    # The last header byte is checked against the high byte of the CRC.
+    header = bytes(11) + bytes([zlib.crc32(data) >> 24])
+    encrypted = bytearray()
+    for byte in header + data:
+        temp = keys[2] | 2
+        encrypted.append(byte ^ (((temp * (temp ^ 1)) >> 8) & 0xFF))
+        update_keys(byte)
+
+    return bytes(encrypted)
+
+
+class StreamEntriesTests(unittest.TestCase):
+    def test_streams_every_member_in_order(self) -> None:
+        buffer = make_archive(ENTRIES, zipfile.ZIP_DEFLATED)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            names = []
+            output = b""
+            for info, handle in archive.stream_entries():
+                names.append(info.filename)
+                self.assertEqual(info.file_size, len(ENTRIES[info.filename]))
+                output += handle.read()
+
+        self.assertEqual(names, list(ENTRIES))
+        self.assertEqual(output, b"".join(ENTRIES.values()))
+
+    def test_previous_member_is_closed_when_advancing(self) -> None:
+        buffer = make_archive(ENTRIES)
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            entries = archive.stream_entries()
+            _info, first = next(entries)
+            self.assertFalse(first.closed)
+            self.assertEqual(first.read(5), b"alpha")
+
+            _info, second = next(entries)
+            self.assertTrue(first.closed)
+            self.assertFalse(second.closed)
+            with self.assertRaises(ValueError):
+                first.read()
+
+            # Only one member is open, so the archive is free once it is closed too.
+            second.close()
+            self.assertEqual(archive.read("c.txt"), b"gamma")
+
+    def test_password(self) -> None:
+        data = b"encrypted contents"
+        member = RawMember(
+            "secret.txt",
+            data,
+            zip_crypto_encrypt(data, PASSWORD),
+            nd_zipfile.ZIP_STORED,
+            flag_bits=0x1,
+        )
+        buffer = make_raw_archive([member])
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.read("secret.txt", pwd=PASSWORD), data)
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            for _info, handle in archive.stream_entries(pwd=PASSWORD):
+                self.assertEqual(handle.read(), data)
+
+            archive.setpassword(PASSWORD)
+            for _info, handle in archive.stream_entries():
+                self.assertEqual(handle.read(), data)
+
+    def test_write_only(self) -> None:
+        with nd_zipfile.ZipFile(io.BytesIO(), "w") as archive:
+            with self.assertRaises(ValueError):
+                archive.stream_entries()
+
+
+if __name__ == "__main__":
+    unittest.main()
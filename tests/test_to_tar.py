@@ -0,0 +1,70 @@
+from __future__ import annotations
+
+import io
+import os
+import tarfile
+import tempfile
+import time
+import unittest
+import zipfile
+
+import nd_zipfile
+from tests.helpers import NEW_YORK, local_timezone
+
+ENTRIES = {
+    "a.txt": (b"alpha" * 100, (2020, 1, 1, 0, 0, 0)),
+    "dir/": (b"", (2021, 6, 15, 12, 30, 0)),
+    "dir/b.bin": (bytes(range(256)) * 10, (2022, 7, 4, 8, 0, 10)),
+    "long/" + "n" * 120 + ".txt": (b"long name", (2023, 11, 5, 1, 30, 0)),
+}
+
+
+def make_dated_archive() -> io.BytesIO:
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w", zipfile.ZIP_DEFLATED) as archive:
+        for name, (data, date_time) in ENTRIES.items():
+            archive.writestr(zipfile.ZipInfo(name, date_time), data)
+
+    buffer.seek(0)
+    return buffer
+
+
+@unittest.skipUnless(hasattr(time, "tzset"), "changing the time zone needs time.tzset")
+class ToTarTests(unittest.TestCase):
+    def check_round_trip(self, compression: str | None) -> None:
+        with tempfile.TemporaryDirectory() as directory, local_timezone(NEW_YORK):
+            output_path = os.path.join(directory, "archive.tar")
+            with nd_zipfile.ZipFile(make_dated_archive(), "r") as archive:
+                archive.to_tar(output_path, compression)
+
+            with tarfile.open(output_path, f"r:{compression or ''}") as tar:
+                members = tar.getmembers()
+                self.assertEqual(
+                    [member.name for member in members],
+                    [name.rstrip("/") for name in ENTRIES],
+                )
+                for member, (name, (data, date_time)) in zip(members, ENTRIES.items()):
+                    self.assertEqual(member.isdir(), name.endswith("/"))
+                    self.assertEqual(member.size, len(data))
+                    expected_mtime = time.mktime(date_time + (0, 0, -1))
+                    self.assertEqual(member.mtime, expected_mtime)
+                    if member.isfile():
+                        handle = tar.extractfile(member)
+                        assert handle is not None
+                        self.assertEqual(handle.read(), data)
+
+                # Midnight in New York, not in UTC.
+                self.assertEqual(members[0].mtime, 1577854800)
+
+    def test_uncompressed(self) -> None:
+        self.check_round_trip(None)
+
+    def test_gzip(self) -> None:
+        self.check_round_trip("gz")
+
+    def test_bzip2(self) -> None:
+        self.check_round_trip("bz2")
+
+
+if __name__ == "__main__":
+    unittest.main()
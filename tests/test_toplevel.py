@@ -0,0 +1,31 @@
+import unittest
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+NESTED = {
+    "README.md": b"readme",
+    "src/": b"",
+    "src/main.rs": b"fn main() {}",
+    "src/nested/deep/mod.rs": b"",
+    "docs/guide/intro.md": b"implied directories",
+    "docs/guide/usage.md": b"",
+    "/absolute.txt": b"leading slash",
+}
+
+
+class ToplevelTests(unittest.TestCase):
+    def test_nested_archive(self) -> None:
+        with nd_zipfile.ZipFile(make_archive(NESTED), "r") as archive:
+            self.assertEqual(
+                archive.toplevel(),
+                {"README.md", "src/", "docs/", "absolute.txt"},
+            )
+
+    def test_empty_archive(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({}), "r") as archive:
+            self.assertEqual(archive.toplevel(), set())
+
+
+if __name__ == "__main__":
+    unittest.main()
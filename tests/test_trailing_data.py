@@ -0,0 +1,75 @@
+from __future__ import annotations
+
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+from unittest import mock
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+ENTRIES = {"a.txt": b"alpha", "dir/b.txt": b"beta"}
+SMALL_JUNK = b"junk" * 10
+# More than the 64 KiB a comment could span, so the record is far from the end.
+LARGE_JUNK = bytes(range(256)) * 800
+
+
+def make_zip64_archive() -> io.BytesIO:
+    """Build an archive with a Zip64 end of central directory record."""
+    buffer = io.BytesIO()
+    with mock.patch.object(zipfile, "ZIP_FILECOUNT_LIMIT", 0):
+        with zipfile.ZipFile(buffer, "w") as archive:
+            for name, data in ENTRIES.items():
+                archive.writestr(name, data)
+
+    return buffer
+
+
+def with_junk(buffer: io.BytesIO, junk: bytes) -> io.BytesIO:
+    return io.BytesIO(buffer.getvalue() + junk)
+
+
+class TrailingDataTests(unittest.TestCase):
+    def assert_readable(self, archive: nd_zipfile.ZipFile) -> None:
+        self.assertEqual(archive.namelist(), list(ENTRIES))
+        for name, data in ENTRIES.items():
+            self.assertEqual(archive.read(name), data)
+        disk_info = archive.disk_info()
+        self.assertEqual(disk_info["disk_number"], 0)
+        self.assertEqual(disk_info["disk_with_central_directory"], 0)
+
+    def test_trailing_junk(self) -> None:
+        for junk in [SMALL_JUNK, LARGE_JUNK]:
+            with self.subTest(junk_size=len(junk)):
+                buffer = with_junk(make_archive(ENTRIES), junk)
+                with nd_zipfile.ZipFile(buffer, "r") as archive:
+                    self.assert_readable(archive)
+                    self.assertFalse(archive.is_zip64())
+                    self.assertNotIn("total_disks", archive.disk_info())
+
+    def test_trailing_junk_after_zip64_records(self) -> None:
+        for junk in [SMALL_JUNK, LARGE_JUNK]:
+            with self.subTest(junk_size=len(junk)):
+                buffer = with_junk(make_zip64_archive(), junk)
+                with nd_zipfile.ZipFile(buffer, "r") as archive:
+                    self.assert_readable(archive)
+                    self.assertTrue(archive.is_zip64())
+                    self.assertEqual(archive.disk_info()["total_disks"], 1)
+
+    def test_trailing_junk_in_a_file(self) -> None:
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "archive.zip")
+            with open(path, "wb") as file:
+                file.write(make_archive(ENTRIES).getvalue() + LARGE_JUNK)
+
+            with nd_zipfile.ZipFile(path, "r") as archive:
+                self.assert_readable(archive)
+
+    def test_junk_without_an_archive(self) -> None:
+        self.assertFalse(nd_zipfile.is_zipfile(io.BytesIO(LARGE_JUNK)))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,41 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+ENTRIES = {"b.txt": b"beta", "a.txt": "alpha, as text", "dir/c.bin": bytes(range(256))}
+
+
+class WriteAllTests(unittest.TestCase):
+    def test_round_trip(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.write_all(ENTRIES)
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            # Members are written in insertion order.
+            self.assertEqual(archive.namelist(), list(ENTRIES))
+            contents = {name: archive.read(name) for name in archive.namelist()}
+
+        expected = {
+            name: data.encode("utf-8") if isinstance(data, str) else data
+            for name, data in ENTRIES.items()
+        }
+        self.assertEqual(contents, expected)
+
+        with zipfile.ZipFile(buffer) as archive:
+            compress_types = {info.compress_type for info in archive.infolist()}
+            self.assertEqual(compress_types, {zipfile.ZIP_DEFLATED})
+
+    def test_read_only(self) -> None:
+        buffer = io.BytesIO()
+        zipfile.ZipFile(buffer, "w").close()
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            with self.assertRaises(ValueError):
+                archive.write_all(ENTRIES)
+
+
+if __name__ == "__main__":
+    unittest.main()
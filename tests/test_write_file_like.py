@@ -0,0 +1,61 @@
+import io
+import unittest
+import zipfile
+
+import nd_zipfile
+
+ENTRIES = {"a.txt": b"alpha", "dir/b.txt": b"beta" * 100}
+
+
+class RecordingBuffer(io.BytesIO):
+    """A buffer that records whether it was flushed after the last write."""
+
+    def __init__(self) -> None:
+        super().__init__()
+        self.flushed = False
+
+    def write(self, data) -> int:  # type: ignore[no-untyped-def]
+        self.flushed = False
+        return super().write(data)
+
+    def flush(self) -> None:
+        self.flushed = True
+        super().flush()
+
+
+class WriteFileLikeTests(unittest.TestCase):
+    def test_write_to_bytes_io_and_read_back(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            for name, data in ENTRIES.items():
+                archive.writestr(name, data)
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.testzip())
+            self.assertEqual(
+                {name: archive.read(name) for name in archive.namelist()}, ENTRIES
+            )
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            for name, data in ENTRIES.items():
+                self.assertEqual(archive.read(name), data)
+
+    def test_close_writes_the_central_directory_and_flushes(self) -> None:
+        buffer = RecordingBuffer()
+        archive = nd_zipfile.ZipFile(buffer, "w")
+        archive.writestr("a.txt", b"alpha")
+        # The central directory is only written on close.
+        self.assertNotIn(b"PK\x05\x06", buffer.getvalue())
+
+        archive.close()
+        self.assertTrue(buffer.flushed)
+        self.assertIn(b"PK\x05\x06", buffer.getvalue())
+        # The buffer is left open for the caller.
+        self.assertFalse(buffer.closed)
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.read("a.txt"), b"alpha")
+
+
+if __name__ == "__main__":
+    unittest.main()
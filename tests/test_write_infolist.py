@@ -0,0 +1,100 @@
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+
+import nd_zipfile
+
+FIELDS = ["filename", "compress_size", "file_size", "CRC", "header_offset"]
+
+
+def fields(infos: list) -> list:
+    return [tuple(getattr(info, field) for field in FIELDS) for info in infos]
+
+
+class WriteInfolistTests(unittest.TestCase):
+    def write_every_way(self, archive: nd_zipfile.ZipFile) -> None:
+        archive.writestr("writestr.txt", b"from bytes" * 100)
+        archive.mkdir("directory")
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "source.txt")
+            with open(path, "wb") as file:
+                file.write(b"from a file" * 100)
+            archive.write(path, "write.txt")
+        with archive.open("open.txt", "w") as handle:
+            handle.write(b"from a handle" * 100)
+        archive.write_iter("write_iter.txt", [b"from chunks"] * 100)
+        archive.writestr("stored.txt", b"stored", nd_zipfile.ZIP_STORED)
+
+    def assert_matches_zipfile(self, compression: int, prefix: bytes = b"") -> None:
+        buffer = io.BytesIO(prefix)
+        buffer.seek(0, io.SEEK_END)
+        archive = nd_zipfile.ZipFile(buffer, "w", compression)
+        self.write_every_way(archive)
+
+        # The last member is only finished once the archive is closed.
+        written = archive.infolist()
+        self.assertEqual(written[-1].compress_size, 0)
+        archive.close()
+        written = archive.infolist()
+
+        with zipfile.ZipFile(buffer) as expected:
+            self.assertEqual(fields(written), fields(expected.infolist()))
+
+    def test_stored(self) -> None:
+        self.assert_matches_zipfile(nd_zipfile.ZIP_STORED)
+
+    def test_deflated(self) -> None:
+        self.assert_matches_zipfile(nd_zipfile.ZIP_DEFLATED)
+
+    def test_bzip2(self) -> None:
+        self.assert_matches_zipfile(nd_zipfile.ZIP_BZIP2)
+
+    def test_prefixed_archive(self) -> None:
+        self.assert_matches_zipfile(nd_zipfile.ZIP_DEFLATED, prefix=b"#!/bin/sh\n")
+
+    def test_finished_members_are_known_before_closing(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.writestr("first.txt", b"first" * 100)
+            archive.writestr("second.txt", b"second" * 100)
+            first, second = archive.infolist()
+            self.assertGreater(first.compress_size, 0)
+            self.assertEqual(first.header_offset, 0)
+            self.assertGreater(second.header_offset, first.compress_size)
+
+        with zipfile.ZipFile(buffer) as expected:
+            self.assertEqual(fields([first]), fields(expected.infolist()[:1]))
+
+    def test_discarded_members_do_not_shift_offsets(self) -> None:
+        def failing_chunks():
+            yield b"partial" * 100
+            raise ValueError("source failed")
+
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.writestr("kept.txt", b"kept" * 100)
+            with self.assertRaises(ValueError):
+                archive.write_iter("discarded.txt", failing_chunks())
+            archive.writestr("after.txt", b"after" * 100)
+        written = archive.infolist()
+
+        with zipfile.ZipFile(buffer) as expected:
+            self.assertEqual(fields(written), fields(expected.infolist()))
+
+    def test_appended_members(self) -> None:
+        buffer = io.BytesIO()
+        with zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("existing.txt", b"existing")
+
+        with nd_zipfile.ZipFile(buffer, "a", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.writestr("appended.txt", b"appended" * 100)
+        written = archive.infolist()
+
+        with zipfile.ZipFile(buffer) as expected:
+            self.assertEqual(fields(written), fields(expected.infolist()))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,50 @@
+from __future__ import annotations
+
+import io
+import unittest
+import zipfile
+from typing import Iterator
+
+import nd_zipfile
+
+
+def chunks() -> Iterator[bytes]:
+    for index in range(100):
+        yield f"chunk {index}\n".encode()
+
+
+class WriteIterTest(unittest.TestCase):
+    def test_generator_round_trips(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            archive.write_iter("generated.txt", chunks())
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.testzip())
+            self.assertEqual(archive.read("generated.txt"), b"".join(chunks()))
+
+    def test_empty_generator_writes_an_empty_member(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.write_iter("empty.txt", iter([]))
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.read("empty.txt"), b"")
+
+    def test_raising_generator_discards_the_member(self) -> None:
+        def failing() -> Iterator[bytes]:
+            yield b"partial"
+            raise OSError("connection lost")
+
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("kept.txt", b"kept")
+            with self.assertRaises(OSError):
+                archive.write_iter("failed.txt", failing())
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(archive.namelist(), ["kept.txt"])
+
+
+if __name__ == "__main__":
+    unittest.main()
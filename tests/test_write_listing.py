@@ -0,0 +1,131 @@
+import datetime
+import io
+import json
+import unittest
+
+import nd_zipfile
+
+
+class WriteListingTests(unittest.TestCase):
+    def setUp(self) -> None:
+        self.archive = nd_zipfile.ZipFile(io.BytesIO(), "w")
+        self.addCleanup(self.archive.close)
+
+    def test_empty_archive(self) -> None:
+        self.assertEqual(len(self.archive), 0)
+        self.assertEqual(list(self.archive), [])
+        self.assertNotIn("a.txt", self.archive)
+        with self.assertRaises(KeyError):
+            self.archive.getinfo("a.txt")
+
+    def test_members_written_so_far(self) -> None:
+        self.archive.writestr("a.txt", b"alpha")
+        self.archive.mkdir("dir")
+        self.archive.write_iter("dir/b.txt", [b"beta"])
+
+        self.assertEqual(len(self.archive), 3)
+        self.assertEqual(list(self.archive), ["a.txt", "dir/", "dir/b.txt"])
+        self.assertEqual(list(self.archive), self.archive.namelist())
+        self.assertIn("dir/b.txt", self.archive)
+        self.assertNotIn("dir/c.txt", self.archive)
+
+        info = self.archive.getinfo("a.txt")
+        self.assertEqual(info.filename, "a.txt")
+        self.assertEqual(info.file_size, 5)
+
+    def test_open_member_is_listed(self) -> None:
+        with self.archive.open("open.txt", "w") as handle:
+            handle.write(b"data")
+            self.assertIn("open.txt", self.archive)
+            self.assertEqual(len(self.archive), 1)
+            self.assertEqual(self.archive.getinfo("open.txt").file_size, 4)
+
+    def test_discarded_member_is_not_listed(self) -> None:
+        def failing_chunks():
+            yield b"partial"
+            raise ValueError("source failed")
+
+        with self.assertRaises(ValueError):
+            self.archive.write_iter("discarded.txt", failing_chunks())
+
+        self.assertNotIn("discarded.txt", self.archive)
+        self.assertEqual(len(self.archive), 0)
+
+    def test_appended_archive_lists_existing_members(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("existing.txt", b"existing")
+
+        with nd_zipfile.ZipFile(buffer, "a") as archive:
+            archive.writestr("appended.txt", b"appended")
+            self.assertEqual(list(archive), ["existing.txt", "appended.txt"])
+            self.assertIn("existing.txt", archive)
+            self.assertEqual(archive.getinfo("existing.txt").file_size, 8)
+
+    def test_metadata_methods_list_members_written_so_far(self) -> None:
+        self.archive.writestr("a.txt", b"alpha" * 10, nd_zipfile.ZIP_DEFLATED)
+        self.archive.mkdir("dir")
+        self.archive.writestr("dir/b.bin", b"beta")
+        self.archive.writestr("dir/c.txt", b"gamma" * 100, nd_zipfile.ZIP_DEFLATED)
+
+        self.assertEqual(self.archive.toplevel(), {"a.txt", "dir/"})
+        self.assertEqual(self.archive.glob("dir/*.txt"), ["dir/c.txt"])
+        self.assertEqual(
+            self.archive.largest(2), [("dir/c.txt", 500), ("a.txt", 50)]
+        )
+        self.assertEqual(self.archive.size_by_extension(), {".txt": 550, ".bin": 4})
+        self.assertEqual(self.archive.unsupported_entries(), [])
+        self.assertEqual(
+            self.archive.method_histogram(),
+            {nd_zipfile.ZIP_STORED: 2, nd_zipfile.ZIP_DEFLATED: 2},
+        )
+        self.assertEqual(self.archive.dominant_compression(), nd_zipfile.ZIP_STORED)
+        yesterday = datetime.datetime.now() - datetime.timedelta(days=1)
+        self.assertEqual(
+            self.archive.modified_since(yesterday), self.archive.namelist()
+        )
+
+        metadata = json.loads(self.archive.metadata_json())
+        self.assertEqual(
+            [entry["filename"] for entry in metadata], self.archive.namelist()
+        )
+        self.assertEqual(metadata[0]["file_size"], 50)
+
+        output = io.StringIO()
+        self.archive.printdir(output)
+        lines = output.getvalue().splitlines()
+        self.assertEqual(len(lines), 5)
+        self.assertTrue(lines[1].startswith("a.txt "))
+
+    def test_methods_that_need_written_records_raise(self) -> None:
+        self.archive.writestr("a.txt", b"alpha")
+        for method in [
+            self.archive.prefetch,
+            self.archive.is_zip64,
+            self.archive.disk_info,
+            self.archive.detect_encoding,
+            self.archive.testzip,
+            lambda: self.archive.split(1000),
+            lambda: self.archive.read("a.txt"),
+        ]:
+            with self.assertRaisesRegex(ValueError, "archive opened as write-only"):
+                method()
+
+    def test_printdir_matches_reading(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            info = nd_zipfile.ZipInfo("a.txt")
+            info.date_time = (2020, 1, 2, 3, 4, 6)
+            archive.writestr(info, b"a")
+            while_writing = io.StringIO()
+            archive.printdir(while_writing)
+
+        after_reading = io.StringIO()
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            archive.printdir(after_reading)
+
+        self.assertEqual(while_writing.getvalue(), after_reading.getvalue())
+
+
+if __name__ == "__main__":
+    unittest.main()
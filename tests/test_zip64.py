@@ -0,0 +1,120 @@
+from __future__ import annotations
+
+import io
+import os
+import tempfile
+import unittest
+import zipfile
+from unittest import mock
+
+import nd_zipfile
+from tests.helpers import make_archive
+
+
+def uses_zip64(info: zipfile.ZipInfo) -> bool:
+    # Members with Zip64 extensions need version 4.5 to extract.
+    return info.extract_version >= zipfile.ZIP64_VERSION
+
+
+class Zip64Tests(unittest.TestCase):
+    def write_every_way(self, archive: nd_zipfile.ZipFile, **kwargs: bool) -> None:
+        """Write one member through each entry point, passing `kwargs` where they apply."""
+        with tempfile.TemporaryDirectory() as directory:
+            path = os.path.join(directory, "source.txt")
+            with open(path, "wb") as file:
+                file.write(b"from a file")
+            archive.write(path, "write.txt")
+
+        archive.writestr("writestr.txt", b"from bytes")
+        with archive.open("open.txt", "w", **kwargs) as handle:
+            handle.write(b"from a handle")
+        archive.write_iter("write_iter.txt", [b"from ", b"chunks"], **kwargs)
+
+    def zip64_usage(self, buffer: io.BytesIO) -> dict[str, bool]:
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertIsNone(archive.testzip())
+            return {info.filename: uses_zip64(info) for info in archive.infolist()}
+
+    def test_small_members_are_written_uniformly_without_zip64(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", nd_zipfile.ZIP_DEFLATED) as archive:
+            self.write_every_way(archive)
+
+        usage = self.zip64_usage(buffer)
+        self.assertEqual(len(usage), 4)
+        self.assertEqual(set(usage.values()), {False})
+
+    def test_force_zip64_applies_to_the_call_only(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            self.write_every_way(archive, force_zip64=True)
+
+        self.assertEqual(
+            self.zip64_usage(buffer),
+            {
+                "write.txt": False,
+                "writestr.txt": False,
+                "open.txt": True,
+                "write_iter.txt": True,
+            },
+        )
+
+    def test_disallowed_zip64_still_writes_small_members(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", allowZip64=False) as archive:
+            self.write_every_way(archive)
+
+        self.assertEqual(set(self.zip64_usage(buffer).values()), {False})
+
+    def test_disallowed_zip64_rejects_forced_members(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", allowZip64=False) as archive:
+            with self.assertRaises(nd_zipfile.LargeZipFile):
+                archive.open("open.txt", "w", force_zip64=True)
+            with self.assertRaises(nd_zipfile.LargeZipFile):
+                archive.write_iter("write_iter.txt", [b"data"], force_zip64=True)
+            self.assertEqual(archive.namelist(), [])
+
+    def test_disallowed_zip64_limits_the_files_count(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w", allowZip64=False) as archive:
+            for index in range(0xFFFF):
+                archive.writestr(str(index), b"")
+            with self.assertRaises(nd_zipfile.LargeZipFile):
+                archive.writestr("one too many", b"")
+            with self.assertRaises(nd_zipfile.LargeZipFile):
+                archive.mkdir("one too many")
+
+        with zipfile.ZipFile(buffer) as archive:
+            self.assertEqual(len(archive.infolist()), 0xFFFF)
+
+    def test_is_zip64_for_a_classic_archive(self) -> None:
+        with nd_zipfile.ZipFile(make_archive({"a.txt": b"alpha"}), "r") as archive:
+            self.assertFalse(archive.is_zip64())
+
+    def test_is_zip64_for_a_zip64_end_of_central_directory(self) -> None:
+        buffer = io.BytesIO()
+        # Make the stdlib think the archive has too many members for a classic record.
+        with mock.patch.object(zipfile, "ZIP_FILECOUNT_LIMIT", 0):
+            with zipfile.ZipFile(buffer, "w") as archive:
+                archive.writestr("a.txt", b"alpha")
+        self.assertIn(b"PK\x06\x06", buffer.getvalue())
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertTrue(archive.is_zip64())
+            self.assertEqual(archive.read("a.txt"), b"alpha")
+
+    def test_is_zip64_for_a_zip64_member(self) -> None:
+        buffer = io.BytesIO()
+        with nd_zipfile.ZipFile(buffer, "w") as archive:
+            archive.writestr("classic.txt", b"classic")
+            with archive.open("zip64.txt", "w", force_zip64=True) as handle:
+                handle.write(b"zip64")
+        self.assertNotIn(b"PK\x06\x06", buffer.getvalue())
+
+        with nd_zipfile.ZipFile(buffer, "r") as archive:
+            self.assertTrue(archive.is_zip64())
+
+
+if __name__ == "__main__":
+    unittest.main()